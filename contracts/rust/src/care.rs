@@ -0,0 +1,85 @@
+//! Looking after plants.  Watering fills a plant's hydration up, and it dries
+//! out again over a couple of days.  A plant that's been left dry loses health,
+//! and only fertilizer brings that back.  Unhealthy plants give less rare harvests.
+
+use near_sdk::json_types::U64;
+use near_sdk::serde::Serialize;
+
+use crate::growth::{Stage, HOUR};
+use crate::token_bank::TokenJSON;
+
+pub const FULL: u8 = 100;
+
+// how long a plant takes to lose a point of hydration (empty in two days)
+const HYDRATION_LOSS: u64 = 48 * HOUR / FULL as u64;
+// ... and, once it's dry, a point of health
+const HEALTH_LOSS: u64 = HOUR;
+// how much health fertilizer gives back
+const FERTILIZER_HEALTH: u8 = 25;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Care {
+    pub hydration: u8,
+    pub health: u8,
+}
+
+impl Care {
+    // a freshly planted plant
+    pub fn fresh() -> Self {
+        Self { hydration: FULL, health: FULL }
+    }
+
+    // how the plant is doing `elapsed` nanoseconds later, if nobody looks after it
+    pub fn after(self, elapsed: u64) -> Self {
+        let wet_for = self.hydration as u64 * HYDRATION_LOSS;
+        let dry_for = elapsed.saturating_sub(wet_for);
+        Self {
+            hydration: (self.hydration as u64).saturating_sub(elapsed / HYDRATION_LOSS) as u8,
+            health: (self.health as u64).saturating_sub(dry_for / HEALTH_LOSS) as u8,
+        }
+    }
+
+    pub fn watered(self) -> Self {
+        Self { hydration: FULL, ..self }
+    }
+
+    pub fn fertilized(self) -> Self {
+        Self { health: self.health.saturating_add(FERTILIZER_HEALTH).min(FULL), ..self }
+    }
+}
+
+// how a plant is doing, as of now
+#[derive(PartialEq, Clone, Debug, Serialize)]
+pub struct PlantStatus {
+    pub vid: TokenJSON,
+    pub stage: Stage,
+    pub hydration: u8,
+    pub health: u8,
+    pub planted_at: U64,
+    pub cared_at: U64, // when it was last watered or fertilized (or planted)
+    pub harvested_at: U64,
+    pub harvests_left: u32,   // this season (see harvest.rs)
+    pub harvest_ready_at: U64, // when its harvest cooldown is over
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dries_out() {
+        let care = Care::fresh();
+        assert_eq!(Care { hydration: 50, health: FULL }, care.after(24 * HOUR), "should be half dry after a day");
+        assert_eq!(Care { hydration: 0, health: FULL }, care.after(48 * HOUR), "should be dry after two days");
+        assert_eq!(Care { hydration: 0, health: 90 }, care.after(58 * HOUR), "should lose health once dry");
+        assert_eq!(0, care.after(365 * 24 * HOUR).health);
+    }
+
+    #[test]
+    fn water_and_fertilize() {
+        let care = Care::fresh().after(60 * HOUR);
+        assert_eq!(Care { hydration: FULL, health: 88 }, care.watered(), "water shouldn't heal");
+        assert_eq!(Care { hydration: 0, health: FULL }, care.fertilized(), "fertilizer should heal up to full");
+        assert_eq!(Care { hydration: 0, health: 25 }, Care { hydration: 0, health: 0 }.fertilized());
+    }
+}
@@ -2,6 +2,9 @@
 
 use near_sdk::{Balance};
 
+use crate::growth::HOUR;
+use crate::rarity::Rarity;
+
 pub type VeggieType = u8;
 pub type VeggieSubType = u8;
 pub type PlantType = VeggieSubType;
@@ -23,6 +26,8 @@ pub mod ptypes {
     pub const COMPLIMENT: PlantType = 4;
     pub const INSULT: PlantType = 5;
     pub const SEED: PlantType = 6;
+    pub const ADVICE: PlantType = 7;
+    pub const LUCKY: PlantType = 8;
 }
 // types of harvest
 pub mod htypes {
@@ -30,94 +35,222 @@ pub mod htypes {
     pub const GENERIC: HarvestType= 0;
 }
 
-// prices to harvest
-pub const P_PRICES: [Balance; 7] = [
+// display names, for token titles
+// array index == PlantType (an int)
+pub const P_NAMES: [&str; 9] = [
+    "",
+    "Oracle Plant",
+    "Portrait Plant",
+    "Money Plant",
+    "Compliment Plant",
+    "Insult Plant",
+    "Seed Plant",
+    "Advice Plant",
+    "Lucky Numbers Plant"
+];
+
+pub const H_NAMES: [&str; 9] = [
+    "",
+    "Fortune",
+    "Portrait",
+    "", // money (can't harvest)
+    "Compliment",
+    "Insult",
+    "Seed",
+    "Advice",
+    "Lucky Numbers"
+];
+
+// NEP-177 contract metadata
+pub const NFT_NAME: &str = "Plantary";
+pub const NFT_SYMBOL: &str = "PLANT";
+pub const NFT_ICON: &str = "data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 32 32'%3E%3Cpath d='M16 30V14' stroke='%23385723' stroke-width='2'/%3E%3Cpath d='M16 16C16 8 10 4 4 4c0 8 6 12 12 12zM16 14c0-6 5-10 12-10 0 7-5 10-12 10z' fill='%2370ad47'/%3E%3C/svg%3E";
+pub const NFT_BASE_URI: &str = "https://arweave.net";
+
+// starting prices to mint each type of plant, in NEAR (see prices.rs)
+pub const P_PRICES: [Balance; 9] = [
     0, // generic
     10, // oracle
     20, // portrait
     30, // money 
-    0,
-    0,
-    0
+    10, // compliment
+    10, // insult
+    40, // seed
+    10, // advice
+    15, // lucky numbers
 ];
 
-// prices to harvest
-pub const H_PRICES: [Balance; 7] = [
-    0, // generic
-    5, // oracle
-    5, // portrait
-    0, // money (can't harvest)
-    5,
+// starting price to mint a random plant, in NEAR
+pub const RANDOM_PLANT_PRICE: Balance = 10;
+
+// starting weights for drawing a random plant's type: a type with weight 20 is twice as likely
+// as one with 10, and one with 0 is never drawn (see prices.rs)
+pub const RANDOM_WEIGHTS: [u32; 9] = [
+    0,  // generic
+    30, // oracle
+    20, // portrait
+    10, // money
+    10,
+    10,
     5,
-    50
+    10,
+    5
 ];
 
-// nested array of meta_urls for possible plants!
+// what harvesting each type of plant gives, and costs, and how often it can be done
+// (a plant's vigor stretches or shrinks the cooldown and the harvests per season; see harvest.rs)
+pub struct HarvestPolicy {
+    pub harvestable: bool,
+    pub vtype: VeggieType,   // harvests, or (for seed plants) new plants
+    pub yields: HarvestType, // the type of harvest it gives (new plants' types come from the seed plant's DNA)
+    pub price: Balance,      // starting price, in NEAR (see prices.rs)
+    pub cooldown: u64,       // time between harvests, in nanoseconds
+    pub per_season: u32,     // harvests per growth season
+}
+
+impl HarvestPolicy {
+    pub const fn yields(yields: HarvestType, price: Balance) -> Self {
+        Self { harvestable: true, vtype: vtypes::HARVEST, yields, price, cooldown: 0, per_season: 1 }
+    }
+
+    pub const fn seeds(price: Balance) -> Self {
+        Self { vtype: vtypes::PLANT, ..Self::yields(htypes::GENERIC, price) }
+    }
+
+    pub const fn none() -> Self {
+        Self { harvestable: false, vtype: vtypes::HARVEST, yields: htypes::GENERIC, price: 0, cooldown: 0, per_season: 0 }
+    }
+
+    pub const fn limited(self, cooldown_hours: u64, per_season: u32) -> Self {
+        Self { cooldown: cooldown_hours * HOUR, per_season, ..self }
+    }
+}
+
 // array index == PlantType (an int)
-// (for demo only ... this should be a web data struct someplace ...)
-
-// lazy global statics, from https://rust-lang-nursery.github.io/rust-cookbook/mem/global_static.html
-
-use lazy_static::lazy_static;
-use std::collections::HashMap;
-
-lazy_static! {
-    // pool of possible plants, sorted by plant type
-    pub static ref P_POOL: HashMap<u8, Vec<&'static str>> = {
-        let mut map = HashMap::new();
-        map.insert(ptypes::ORACLE, vec![
-    "https://3bvdryfdm3sswevmvr3poka2ucda5dfqag3bz4td72affctbmaea.arweave.net/2Go44KNm5SsSrKx29ygaoIYOjLABthzyY_6AUophYAg", 
-    "https://vwanp7rn32rioq6ofcvglo52sgdrctcfkc4v7uiy7bbimtzijz3q.arweave.net/rYDX_i3eoodDziiqZbu6kYcRTEVQuV_RGPhChk8oTnc",
-    "https://arweave.net/VoJ1Wx6xTflalopLxOuj7TpO8pC0urYB-vLiZ1FxYno",
-    "https://arweave.net/33wa-6hW_vQAbkQ4a5ZXX7HGJMGR3M8ej-z9dvcnJ8k",
-        ]);
-        map.insert(ptypes::PORTRAIT, vec![
-    "https://rsigfpny3j3uwohxfeo7tdkdvw6yhaefxt6d3uq7kajtpaqtdfwq.arweave.net/jJBivbjad0s49ykd-Y1Drb2DgIW8_D3SH1ATN4ITGW0",
-    "https://arweave.net/fo--Wlh83Ka83zVQqliiwFq_4zbc1H7vrZNlvA_Gkek",
-    "https://arweave.net/1oDuE6UNrNC4Y_aNfhp_Vde_II2ZIFsuRT1hBYbRydc",
-    "https://arweave.net/M7uwpTyRIZIohXBgIZUqoYDyxq1GyH3fkoT7CvN2iLE",
-        ]);
-        map.insert(ptypes::MONEY, vec![
-    "https://rj32ukhcq4hdq7nux3rntp5ffdk3ff2kzjcalpy3mc7batjytoza.arweave.net/ineqKOKHDjh9tL7i2b-lKNWyl0rKRAW_G2C-EE04m7I",
-    "https://b2zjlf2zplj5we2bdar6p6smu3o6fdu7o7ed23takt63lck6peoq.arweave.net/DrKVl1l609sTQRgj5_pMpt3ijp93yD1uYFT9tYleeR0",
-    "https://arweave.net/q8RPmg2qf6nfE4Gc1at7bqOBuWbSsEtzxvdICb1NYzk",
-    "https://arweave.net/dPBN2DGba13xI7IFqBoczspsHbTXUvmZ9sjKIuhU28o",
-        ]);
-        map
-    };
-    // pool of possible harvests, sorted by plant type
-    pub static ref H_POOL: HashMap<u8, Vec<&'static str>> = {
-        let mut map = HashMap::new();
-        map.insert(ptypes::ORACLE, vec![
-    "https://arweave.net/v63RbTVHhGKr7UNMmwMjBtKepk1I26UB4yxPhJVSkcg",
-    "https://arweave.net/hvOKZAw3miEA8BE4VewzH9io4fNsSWyZpGZaSmhr-l8",
-    "https://arweave.net/B_c8uZaUFIA8hjLDVr3v4IR6aRT-zzvCaE0cqWgVURc",
-    "https://arweave.net/mGhn0lNxVB6rfon61c9rRioMKL3ZsbjrVJA0qt9St4o",
-    "https://arweave.net/3xMnn8J1ViLX8uHRfDxMpAZS2tSwT7VWrdjDT3fV2xQ",
-    "https://arweave.net/_q0UfS76GMma9PR-XMavRI8ozipY_cmgoi6TFS_eHOg",
-    "https://arweave.net/-Nxk3noWBskl8kbfxhCZFspD7v9lf79iJt1bQ2TCTzw",
-    "https://arweave.net/zXIyOvf6q42eiVnixg6EK_RmFfxlZaFuaQgvs9b6Y8c",
-    "https://arweave.net/u2wER7li2oXgMXfRUs22oERc-XUsn2Ph9yBsZPrvcBc",
-    "https://arweave.net/eQQKfobStzP8dHIzbXYjJCMKQR1owIZ5ljjwX3xvz7I",
-    "https://arweave.net/eYJ3Ie8K3sVwvXSH5xtXtyi8PizJCzkXK4n7MCCMunE", // Oracle Voucher, by Oculardelusion
-        ]);
-        map.insert(ptypes::PORTRAIT, vec![
-    "https://arweave.net/tmOUL9xwL8LQb_E5kOldLaF0mrZLg9rSMYpoTGgdkU8",
-    "https://arweave.net/tvCQax-rq-oDvRdy-QnBp5orrjSP04Y-dNxXC3maTkI",
-    "https://arweave.net/CJyoNeeDM_Vco0l4-7y434_pe4hBhWEE9vvh5XqMd4k",
-    "https://arweave.net/hZ3etzVzsaXX6utSldyfvIvp0JUoFISuA72vJpNKa8s",
-    "https://arweave.net/63vqengRMJiiBU-YmVpRH9nDclZB_f3zNVsn0wtcqg4",
-    "https://arweave.net/mTFjapdnAWqWLXOqySLM0cHX2AOcYYWpoSPArBA_suk",
-    "https://arweave.net/eaCk3l8Oi3MqNi7lKMRRC7gR5zRXO9JfbJc80OquHQk",
-    "https://arweave.net/30kVPubXOw6vJce923j6Nv27jWl39AeS4EcpMijCmZA",
-    "https://arweave.net/1xw0bRDaU-CV7hsOGnP51ZWr5_zVk21Qxu8h_jcX-tg",
-    "https://arweave.net/FK4nE9euzIoEx4QOPpocSKDK0wjrPwcxX0cjxx8Km5I",
-    "https://arweave.net/zIU6uG94XnwtTeEzHB3GSikBTtErmE3fgWCZV744tZE",
-    "https://arweave.net/usIqVRzLyFNGENUgeV8c5-zjzEptOJZa23BkUDiU3cU",
-    "https://arweave.net/EDiBwvIYUmT5cmPqbW02HOuFZnHUPoTNX_ri3N2BeTg",
-    "https://arweave.net/LJkU3DnETelIpCdn6l6v-ZDUdY0LzW77G3qNPkRl7cs", // Dennis, by Ilan Katin
-    "https://arweave.net/MiV3Xi4qmRjquQJTK3usefWMy62DJ1TCemb0jsY1NBo", // Portrait Voucher, by Oculardelusion
-        ]);
-        map
-    };
+pub const H_POLICY: [HarvestPolicy; 9] = [
+    HarvestPolicy::none(),                                      // generic
+    HarvestPolicy::yields(ptypes::ORACLE, 5).limited(6, 3),     // fortunes
+    HarvestPolicy::yields(ptypes::PORTRAIT, 5).limited(12, 2),  // portraits
+    HarvestPolicy::none(),                                      // money
+    HarvestPolicy::yields(ptypes::COMPLIMENT, 5).limited(4, 4),
+    HarvestPolicy::yields(ptypes::INSULT, 5).limited(4, 4),
+    HarvestPolicy::seeds(50).limited(24, 1),                    // new plants
+    HarvestPolicy::yields(ptypes::ADVICE, 5).limited(6, 3),
+    HarvestPolicy::yields(ptypes::LUCKY, 5).limited(12, 2),
+];
+
+// starting price to breed two plants
+pub const BREED_PRICE: Balance = 10;
+
+// how long a plant has to rest after breeding, in nanoseconds (one day)
+pub const BREED_COOLDOWN: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+// starting price to fertilize a plant
+pub const FERTILIZER_PRICE: Balance = 1;
+
+// what the contract pays to store a byte (1 NEAR per 100kb), in yoctoNEAR.  Submitting a gene or approving
+// an account for a token costs what it stores.
+pub const STORAGE_PRICE_PER_BYTE: Balance = 10_000_000_000_000_000_000;
+
+// how long an owner's nickname for a veggie can be, in characters
+pub const MAX_NICKNAME: usize = 32;
+
+// how many other plants can lend their DNA to a harvest
+pub const MAX_INFLUENCERS: usize = 4;
+
+// artist's cut of every resale, in basis points (10000 == 100%), unless a gene says otherwise
+pub const ARTIST_ROYALTY_BPS: u32 = 1000;
+
+// an artwork that can be attached to a new veggie.
+// meta_url points to the artist's JSON on arweave; the rest is what we know about it on-chain.
+// (these are only the genome's starting genes; see genome.rs)
+pub struct Artwork {
+    pub meta_url: &'static str,
+    pub title: Option<&'static str>,
+    pub description: Option<&'static str>,
+    pub artist: Option<&'static str>,
+    pub rarity: Rarity,
+}
+
+impl Artwork {
+    pub const fn new(meta_url: &'static str) -> Self {
+        Self { meta_url, title: None, description: None, artist: None, rarity: Rarity::Common }
+    }
+
+    pub const fn credited(meta_url: &'static str, title: &'static str, artist: &'static str) -> Self {
+        Self { meta_url, title: Some(title), description: None, artist: Some(artist), rarity: Rarity::Common }
+    }
+
+    pub const fn described(meta_url: &'static str, title: &'static str, description: &'static str, artist: &'static str) -> Self {
+        Self { meta_url, title: Some(title), description: Some(description), artist: Some(artist), rarity: Rarity::Common }
+    }
+
+    // artworks are common unless we say otherwise
+    pub const fn tier(self, rarity: Rarity) -> Self {
+        Self { rarity, ..self }
+    }
 }
+
+// the artworks plantary launched with, which seed the genome when the contract is created.
+// (for demo only ... new artwork goes into the genome, on-chain.)
+
+// possible plants, by plant type
+pub const P_ART: &[(PlantType, Artwork)] = &[
+    (ptypes::ORACLE, Artwork::described("https://3bvdryfdm3sswevmvr3poka2ucda5dfqag3bz4td72affctbmaea.arweave.net/2Go44KNm5SsSrKx29ygaoIYOjLABthzyY_6AUophYAg",
+        "Phytia perolatta",
+        "No time for frills and drills. This Oracle Plant goes straight to the point, unceremoniously cracking open your future. Pearls of wisdom from the swine.",
+        "ilan katin")),
+    (ptypes::ORACLE, Artwork::described("https://vwanp7rn32rioq6ofcvglo52sgdrctcfkc4v7uiy7bbimtzijz3q.arweave.net/rYDX_i3eoodDziiqZbu6kYcRTEVQuV_RGPhChk8oTnc",
+        "Phythia triangularis",
+        "This Oracle Plant opens its fleeting all-seeing eye flower inside a triangle, and its fruit matures as a crystal ball. It’s an air plant nourished by wi-fi emissions nearby, which it uses to glean bits of truth fed into its fortune-photosyntethysing organelles.",
+        "Marigold")),
+    (ptypes::ORACLE, Artwork::new("https://arweave.net/VoJ1Wx6xTflalopLxOuj7TpO8pC0urYB-vLiZ1FxYno")),
+    (ptypes::ORACLE, Artwork::new("https://arweave.net/33wa-6hW_vQAbkQ4a5ZXX7HGJMGR3M8ej-z9dvcnJ8k")),
+    (ptypes::PORTRAIT, Artwork::described("https://rsigfpny3j3uwohxfeo7tdkdvw6yhaefxt6d3uq7kajtpaqtdfwq.arweave.net/jJBivbjad0s49ykd-Y1Drb2DgIW8_D3SH1ATN4ITGW0",
+        "Effigia narcisa",
+        "Mirror mirror, on the wall, who’s the fairest portrait of them all? This Portrait Plant brings forth to the world its best face, all sparkle and glitz. Of course, in the end, beauty is in the eye of the beholder.",
+        "ilan katin")),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/fo--Wlh83Ka83zVQqliiwFq_4zbc1H7vrZNlvA_Gkek")),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/1oDuE6UNrNC4Y_aNfhp_Vde_II2ZIFsuRT1hBYbRydc")),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/M7uwpTyRIZIohXBgIZUqoYDyxq1GyH3fkoT7CvN2iLE")),
+    (ptypes::MONEY, Artwork::described("https://rj32ukhcq4hdq7nux3rntp5ffdk3ff2kzjcalpy3mc7batjytoza.arweave.net/ineqKOKHDjh9tL7i2b-lKNWyl0rKRAW_G2C-EE04m7I",
+        "Fortunatta glutonis",
+        "Highly symbolic of our current wealth paradigm, this exquisite Money Plant stores non-stop information in its bulging leaves, growing more and more in size until they memetically burst out into the world.",
+        "ilan katin")),
+    (ptypes::MONEY, Artwork::described("https://b2zjlf2zplj5we2bdar6p6smu3o6fdu7o7ed23takt63lck6peoq.arweave.net/DrKVl1l609sTQRgj5_pMpt3ijp93yD1uYFT9tYleeR0",
+        "Fortunatta elegans",
+        "The opposite of stingy, this Money Plant is carved in jewel tattoos. While the branches fan out graciously, its paper leaves ruffle in the wind, spreading abundance.",
+        "Marigold")),
+    (ptypes::MONEY, Artwork::new("https://arweave.net/q8RPmg2qf6nfE4Gc1at7bqOBuWbSsEtzxvdICb1NYzk")),
+    (ptypes::MONEY, Artwork::new("https://arweave.net/dPBN2DGba13xI7IFqBoczspsHbTXUvmZ9sjKIuhU28o")),
+];
+
+// possible harvests, by the type of plant they grow on, and how rare each is
+pub const H_ART: &[(PlantType, Artwork)] = &[
+    (ptypes::ORACLE, Artwork::new("https://arweave.net/v63RbTVHhGKr7UNMmwMjBtKepk1I26UB4yxPhJVSkcg")),
+    (ptypes::ORACLE, Artwork::new("https://arweave.net/hvOKZAw3miEA8BE4VewzH9io4fNsSWyZpGZaSmhr-l8")),
+    (ptypes::ORACLE, Artwork::new("https://arweave.net/B_c8uZaUFIA8hjLDVr3v4IR6aRT-zzvCaE0cqWgVURc")),
+    (ptypes::ORACLE, Artwork::new("https://arweave.net/mGhn0lNxVB6rfon61c9rRioMKL3ZsbjrVJA0qt9St4o")),
+    (ptypes::ORACLE, Artwork::new("https://arweave.net/3xMnn8J1ViLX8uHRfDxMpAZS2tSwT7VWrdjDT3fV2xQ")),
+    (ptypes::ORACLE, Artwork::new("https://arweave.net/_q0UfS76GMma9PR-XMavRI8ozipY_cmgoi6TFS_eHOg")),
+    (ptypes::ORACLE, Artwork::new("https://arweave.net/-Nxk3noWBskl8kbfxhCZFspD7v9lf79iJt1bQ2TCTzw").tier(Rarity::Rare)),
+    (ptypes::ORACLE, Artwork::new("https://arweave.net/zXIyOvf6q42eiVnixg6EK_RmFfxlZaFuaQgvs9b6Y8c").tier(Rarity::Rare)),
+    (ptypes::ORACLE, Artwork::new("https://arweave.net/u2wER7li2oXgMXfRUs22oERc-XUsn2Ph9yBsZPrvcBc").tier(Rarity::UltraRare)),
+    (ptypes::ORACLE, Artwork::new("https://arweave.net/eQQKfobStzP8dHIzbXYjJCMKQR1owIZ5ljjwX3xvz7I").tier(Rarity::Legendary)),
+    (ptypes::ORACLE, Artwork::credited("https://arweave.net/eYJ3Ie8K3sVwvXSH5xtXtyi8PizJCzkXK4n7MCCMunE", "Oracle Voucher", "Oculardelusion").tier(Rarity::Mythic)),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/tmOUL9xwL8LQb_E5kOldLaF0mrZLg9rSMYpoTGgdkU8")),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/tvCQax-rq-oDvRdy-QnBp5orrjSP04Y-dNxXC3maTkI")),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/CJyoNeeDM_Vco0l4-7y434_pe4hBhWEE9vvh5XqMd4k")),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/hZ3etzVzsaXX6utSldyfvIvp0JUoFISuA72vJpNKa8s")),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/63vqengRMJiiBU-YmVpRH9nDclZB_f3zNVsn0wtcqg4")),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/mTFjapdnAWqWLXOqySLM0cHX2AOcYYWpoSPArBA_suk")),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/eaCk3l8Oi3MqNi7lKMRRC7gR5zRXO9JfbJc80OquHQk")),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/30kVPubXOw6vJce923j6Nv27jWl39AeS4EcpMijCmZA")),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/1xw0bRDaU-CV7hsOGnP51ZWr5_zVk21Qxu8h_jcX-tg").tier(Rarity::Rare)),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/FK4nE9euzIoEx4QOPpocSKDK0wjrPwcxX0cjxx8Km5I").tier(Rarity::Rare)),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/zIU6uG94XnwtTeEzHB3GSikBTtErmE3fgWCZV744tZE").tier(Rarity::Rare)),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/usIqVRzLyFNGENUgeV8c5-zjzEptOJZa23BkUDiU3cU").tier(Rarity::UltraRare)),
+    (ptypes::PORTRAIT, Artwork::new("https://arweave.net/EDiBwvIYUmT5cmPqbW02HOuFZnHUPoTNX_ri3N2BeTg").tier(Rarity::UltraRare)),
+    (ptypes::PORTRAIT, Artwork::credited("https://arweave.net/LJkU3DnETelIpCdn6l6v-ZDUdY0LzW77G3qNPkRl7cs", "Dennis", "Ilan Katin").tier(Rarity::Legendary)),
+    (ptypes::PORTRAIT, Artwork::credited("https://arweave.net/MiV3Xi4qmRjquQJTK3usefWMy62DJ1TCemb0jsY1NBo", "Portrait Voucher", "Oculardelusion").tier(Rarity::Mythic)),
+];
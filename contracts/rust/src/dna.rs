@@ -0,0 +1,188 @@
+//! What a veggie's DNA says about it.
+//! Each trait is read from its own range of bits in the 64-bit DNA, so
+//! traits are independent of each other, and new ones can be added in the
+//! bits that are still free without changing what existing veggies are.
+//!
+//!   bits  0..8   vigor            0-255
+//!   bits  8..16  yield            0-255
+//!   bits 16..24  rarity affinity  0-255
+//!   bits 24..27  color            one of COLORS
+//!   bits 27..30  leaf shape       one of LEAF_SHAPES
+//!   bits 30..34  growth rate      0-15
+//!   bits 34..37  seed type        0-7, which kind of plant a seed plant's seeds grow into
+//!   bits 37..64  not used yet
+//!
+//! A harvest inherits each of its traits from one of the plants it grew from.
+//! A bred plant's DNA is a crossover of its two parents', with the odd mutation;
+//! a plant grown from a seed crosses its parent's DNA with wild (random) DNA.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::Serialize;
+use rand::Rng;
+
+pub const COLORS: [&str; 8] = [
+    "green", "yellow", "orange", "red", "purple", "blue", "white", "black",
+];
+
+pub const LEAF_SHAPES: [&str; 8] = [
+    "round", "oval", "heart", "lance", "needle", "fan", "lobed", "spiral",
+];
+
+// (start bit, number of bits) of each trait
+const VIGOR: (u32, u32) = (0, 8);
+const YIELD: (u32, u32) = (8, 8);
+const RARITY_AFFINITY: (u32, u32) = (16, 8);
+const COLOR: (u32, u32) = (24, 3);
+const LEAF_SHAPE: (u32, u32) = (27, 3);
+const GROWTH: (u32, u32) = (30, 4);
+const SEED_TYPE: (u32, u32) = (34, 3);
+const TRAITS: [(u32, u32); 7] = [VIGOR, YIELD, RARITY_AFFINITY, COLOR, LEAF_SHAPE, GROWTH, SEED_TYPE];
+
+// read a range of bits out of the DNA
+fn bits(dna: u64, (start, len): (u32, u32)) -> u64 {
+    (dna & mask((start, len))) >> start
+}
+
+fn mask((start, len): (u32, u32)) -> u64 {
+    ((1 << len) - 1) << start
+}
+
+// new DNA whose traits each come from one of the parents, picked at random.
+// (the bits no trait uses yet are left as they are.)
+pub fn inherit(dna: u64, parents: &[u64], rng: &mut impl Rng) -> u64 {
+    if parents.is_empty() {
+        return dna;
+    }
+    TRAITS.iter().fold(dna, |dna, t| {
+        let parent = parents[rng.gen_range(0, parents.len())];
+        (dna & !mask(*t)) | (parent & mask(*t))
+    })
+}
+
+// each bit of a bred plant's DNA has a 1 in MUTATION_ODDS chance of flipping
+const MUTATION_ODDS: u32 = 64;
+
+// Single-point crossover: the low bits (up to a random point) come from one parent,
+// and the rest from the other.  Then mutate.
+pub fn cross(a: u64, b: u64, rng: &mut impl Rng) -> u64 {
+    let point = rng.gen_range(1, 64);
+    let low = (1u64 << point) - 1;
+    let (first, second) = if rng.gen() { (a, b) } else { (b, a) };
+    let mut dna = (first & low) | (second & !low);
+    for bit in 0..64 {
+        if rng.gen_range(0, MUTATION_ODDS) == 0 {
+            dna ^= 1 << bit;
+        }
+    }
+    dna
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize, BorshDeserialize, BorshSerialize)]
+pub struct Traits {
+    // how strong the plant is
+    pub vigor: u8,
+    // how much the plant gives when harvested
+    pub yields: u8,
+    // how likely the plant's harvests are to be rare
+    pub rarity_affinity: u8,
+    pub color: String,
+    pub leaf_shape: String,
+    // how fast the plant goes through its growth stages
+    pub growth: u8,
+    // what a seed plant's seeds grow into (see PlantaryContract::seedling_type)
+    pub seed_type: u8,
+}
+
+impl Traits {
+    pub fn from_dna(dna: u64) -> Self {
+        Self {
+            vigor: bits(dna, VIGOR) as u8,
+            yields: bits(dna, YIELD) as u8,
+            rarity_affinity: bits(dna, RARITY_AFFINITY) as u8,
+            color: COLORS[bits(dna, COLOR) as usize].to_string(),
+            leaf_shape: LEAF_SHAPES[bits(dna, LEAF_SHAPE) as usize].to_string(),
+            growth: bits(dna, GROWTH) as u8,
+            seed_type: bits(dna, SEED_TYPE) as u8,
+        }
+    }
+
+    // what a plant's traits come to with others helping: the others, taken together,
+    // count as much as the plant itself.  (their looks don't rub off.)
+    pub fn blend(&self, others: &[Traits]) -> Self {
+        if others.is_empty() {
+            return self.clone();
+        }
+        let mix = |own: u8, their: fn(&Traits) -> u8| {
+            let theirs = others.iter().map(|t| their(t) as u32).sum::<u32>() / others.len() as u32;
+            ((own as u32 + theirs) / 2) as u8
+        };
+        Self {
+            vigor: mix(self.vigor, |t| t.vigor),
+            yields: mix(self.yields, |t| t.yields),
+            rarity_affinity: mix(self.rarity_affinity, |t| t.rarity_affinity),
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traits_from_bits() {
+        //              shape=lobed  color=purple rarity  yield  vigor
+        let dna: u64 = (6 << 27) | (4 << 24) | (0x80 << 16) | (0x11 << 8) | 0xff;
+        let t = Traits::from_dna(dna);
+        assert_eq!(255, t.vigor);
+        assert_eq!(0x11, t.yields);
+        assert_eq!(0x80, t.rarity_affinity);
+        assert_eq!("purple", t.color);
+        assert_eq!("lobed", t.leaf_shape);
+        assert_eq!(5, Traits::from_dna(5 << 34).seed_type);
+    }
+
+    #[test]
+    fn blend_traits() {
+        let weak = Traits::from_dna(0);
+        let strong = Traits::from_dna(!0);
+        let blended = weak.blend(&[strong.clone(), strong]);
+        assert_eq!(127, blended.vigor, "helpers should count as much as the plant");
+        assert_eq!(127, blended.rarity_affinity);
+        assert_eq!("green", blended.color, "looks shouldn't blend");
+        assert_eq!(weak, weak.blend(&[]));
+    }
+
+    #[test]
+    fn inherit_traits() {
+        let mut rng: rand_chacha::ChaCha8Rng = rand_seeder::Seeder::from("test").make_rng();
+        let traits = Traits::from_dna(inherit(!0, &[0x1234_5678], &mut rng));
+        assert_eq!(Traits::from_dna(0x1234_5678), traits, "only parent's traits not inherited");
+
+        let dna = inherit(0, &[0x0000_0000, 0x1f_ffff_ffff], &mut rng);
+        assert_eq!(0, dna >> 37, "unused bits should be left alone");
+        for t in TRAITS.iter() {
+            assert!(bits(dna, *t) == 0 || bits(dna, *t) == mask((0, t.1)), "trait at bit {} mixed its parents", t.0);
+        }
+    }
+
+    #[test]
+    fn cross_parents() {
+        let mut rng: rand_chacha::ChaCha8Rng = rand_seeder::Seeder::from("test").make_rng();
+        let (a, b) = (0u64, !0u64);
+        let mut from_a = 0;
+        for _ in 0..100 {
+            let dna = cross(a, b, &mut rng);
+            assert_ne!(a, dna, "child is a clone");
+            assert_ne!(b, dna, "child is a clone");
+            from_a += (!dna).count_ones();
+        }
+        // half the bits from each parent, on average
+        assert!(from_a > 2800 && from_a < 3600, "crossover is lopsided: {} of 6400 bits from a", from_a);
+    }
+
+    #[test]
+    fn unused_bits_ignored() {
+        assert_eq!(Traits::from_dna(0), Traits::from_dna(!0 << 37), "high bits changed the traits");
+    }
+}
@@ -0,0 +1,116 @@
+//! NEP-297 event logs, so that indexers can follow what happens to our tokens:
+//! https://github.com/near/NEPs/blob/master/neps/nep-0297.md
+//! The nft_* events follow NEP-171; plantary_harvest and plantary_breed are our own.
+
+use near_sdk::{env, AccountId};
+use near_sdk::serde_json::{json, Map, Value};
+
+use crate::token_bank::TokenId;
+
+pub const NFT_STANDARD: &str = "nep171";
+pub const NFT_STANDARD_VERSION: &str = "1.0.0";
+pub const PLANTARY_STANDARD: &str = "plantary";
+pub const PLANTARY_STANDARD_VERSION: &str = "1.0.0";
+
+// the log line for an event.  (data is a list of event bodies.)
+pub fn event_log(standard: &str, version: &str, event: &str, data: Value) -> String {
+    let event = json!({
+        "standard": standard,
+        "version": version,
+        "event": event,
+        "data": data,
+    });
+    format!("EVENT_JSON:{}", event)
+}
+
+// token IDs are strings in events, like everywhere else in JSON.
+fn token_ids(ids: &[TokenId]) -> Value {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+// the optional fields are left out, not null.
+fn insert_some(body: &mut Map<String, Value>, key: &str, value: Option<&str>) {
+    if let Some(value) = value {
+        body.insert(key.to_string(), value.into());
+    }
+}
+
+pub fn nft_mint(owner_id: &AccountId, ids: &[TokenId], memo: Option<&str>) {
+    let mut body = Map::new();
+    body.insert("owner_id".to_string(), owner_id.as_str().into());
+    body.insert("token_ids".to_string(), token_ids(ids));
+    insert_some(&mut body, "memo", memo);
+    env::log(event_log(NFT_STANDARD, NFT_STANDARD_VERSION, "nft_mint", json!([body])).as_bytes());
+}
+
+// authorized_id is whoever moved the token, if that wasn't its owner.
+pub fn nft_transfer(old_owner_id: &AccountId, new_owner_id: &AccountId, ids: &[TokenId], authorized_id: Option<&str>, memo: Option<&str>) {
+    let mut body = Map::new();
+    insert_some(&mut body, "authorized_id", authorized_id);
+    body.insert("old_owner_id".to_string(), old_owner_id.as_str().into());
+    body.insert("new_owner_id".to_string(), new_owner_id.as_str().into());
+    body.insert("token_ids".to_string(), token_ids(ids));
+    insert_some(&mut body, "memo", memo);
+    env::log(event_log(NFT_STANDARD, NFT_STANDARD_VERSION, "nft_transfer", json!([body])).as_bytes());
+}
+
+pub fn nft_burn(owner_id: &AccountId, ids: &[TokenId], authorized_id: Option<&str>, memo: Option<&str>) {
+    let mut body = Map::new();
+    body.insert("owner_id".to_string(), owner_id.as_str().into());
+    body.insert("token_ids".to_string(), token_ids(ids));
+    insert_some(&mut body, "authorized_id", authorized_id);
+    insert_some(&mut body, "memo", memo);
+    env::log(event_log(NFT_STANDARD, NFT_STANDARD_VERSION, "nft_burn", json!([body])).as_bytes());
+}
+
+// a plant bore fruit: token_id was harvested from parent_id, with help from any influencers.
+pub fn plantary_harvest(owner_id: &AccountId, token_id: TokenId, parent_id: TokenId, influencer_ids: &[TokenId], dna: u64) {
+    let mut body = json!({
+        "owner_id": owner_id,
+        "token_id": token_id.to_string(),
+        "parent_id": parent_id.to_string(),
+        "dna": dna.to_string(),
+    });
+    if !influencer_ids.is_empty() {
+        body["influencer_ids"] = token_ids(influencer_ids);
+    }
+    env::log(event_log(PLANTARY_STANDARD, PLANTARY_STANDARD_VERSION, "plantary_harvest", json!([body])).as_bytes());
+}
+
+// two plants were crossed: token_id is their child.
+pub fn plantary_breed(owner_id: &AccountId, token_id: TokenId, parent_ids: &[TokenId], dna: u64, generation: u32) {
+    let body = json!({
+        "owner_id": owner_id,
+        "token_id": token_id.to_string(),
+        "parent_ids": token_ids(parent_ids),
+        "dna": dna.to_string(),
+        "generation": generation,
+    });
+    env::log(event_log(PLANTARY_STANDARD, PLANTARY_STANDARD_VERSION, "plantary_breed", json!([body])).as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_log_format() {
+        let log = event_log(NFT_STANDARD, NFT_STANDARD_VERSION, "nft_mint", json!([{ "owner_id": "joe.testnet", "token_ids": token_ids(&[19, 42]) }]));
+        assert!(log.starts_with("EVENT_JSON:{"), "missing the EVENT_JSON prefix");
+
+        let event: Value = near_sdk::serde_json::from_str(&log["EVENT_JSON:".len()..]).unwrap();
+        assert_eq!("nep171", event["standard"]);
+        assert_eq!("1.0.0", event["version"]);
+        assert_eq!("nft_mint", event["event"]);
+        assert_eq!(json!(["19", "42"]), event["data"][0]["token_ids"], "token IDs should be strings");
+    }
+
+    #[test]
+    fn optional_fields_left_out() {
+        let mut body = Map::new();
+        insert_some(&mut body, "memo", None);
+        insert_some(&mut body, "authorized_id", Some("mike.testnet"));
+        assert!(!body.contains_key("memo"), "empty memo should be left out");
+        assert_eq!(Some(&json!("mike.testnet")), body.get("authorized_id"));
+    }
+}
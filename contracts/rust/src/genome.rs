@@ -0,0 +1,459 @@
+//! The genome: an on-chain registry of the artworks ("genes") that new veggies are made from.
+//! Genes are pooled by veggie type and subtype, e.g. oracle plants, or the harvests of portrait plants.
+//! New art (as uploaded by intake.html) is added here and published without redeploying the contract;
+//! a gene that is retired, or has reached its max supply, is no longer drawn.
+//! Artists can also submit genes themselves; those wait for a curator to approve (and price) them.
+//! Each gene also has a rarity tier (see rarity.rs), which harvests are drawn by.
+//! A plant gene can instead be for one growth stage (see growth.rs): those aren't minted,
+//! but show what plants of their type look like at that stage.
+
+use near_sdk::collections::{UnorderedMap, UnorderedSet, Vector};
+use near_sdk::{env, json_types::{Base64VecU8, U64, U128}, AccountId, Balance};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::constants::{vtypes, Artwork, VeggieType, VeggieSubType, P_ART, H_ART, P_NAMES, ARTIST_ROYALTY_BPS};
+use crate::payout::{Royalty, ONE_HUNDRED_PERCENT_BPS};
+use crate::rarity::{Rarity, TIERS};
+use crate::growth::Stage;
+use crate::token_bank::page_of;
+
+pub type GeneId = u64;
+// the gene_id of veggies of a type nobody has made art for yet (their art is drawn from their DNA; see render.rs)
+pub const NO_GENE: GeneId = GeneId::MAX;
+type PoolKey = (VeggieType, VeggieSubType);
+type TierKey = (VeggieType, VeggieSubType, Rarity);
+type StageKey = (VeggieType, VeggieSubType, Stage);
+
+pub trait Genes {
+    // Add (and publish) a gene in the pool for its veggie type and subtype; returns its ID.  Curators only.
+    fn add_gene(&mut self, gene: GeneSpec) -> U64;
+
+    // Artists: queue your artwork for the curators.  Royalties go to the caller unless the gene names an artist_id.
+    // (any price in the gene is ignored; the curators set that.)
+    // Attach enough to pay for the storage the submission takes up (STORAGE_PRICE_PER_BYTE a byte); any more is refunded.
+    fn submit_gene(&mut self, gene: GeneSpec) -> U64;
+
+    // Publish a pending gene, at the given price (in NEAR) and max supply (None == unlimited).  Curators only.
+    fn approve_gene(&mut self, gene_id: U64, price: U128, max_supply: Option<U64>);
+
+    // Turn down a pending gene.  Curators only.
+    fn reject_gene(&mut self, gene_id: U64);
+
+    // Replace what we know about a gene.  (its pool can't change, and its supply can't drop below what's minted.)
+    fn update_gene(&mut self, gene_id: U64, gene: GeneSpec);
+
+    // Stop making new veggies from a gene.  Veggies already made from it are unaffected.
+    fn retire_gene(&mut self, gene_id: U64);
+
+    fn get_gene(&self, gene_id: U64) -> GeneJSON;
+
+    // A page of the published genes in one pool, retired ones included.  page_size 0 gets the whole pool.
+    fn get_genes(&self, vtype: VeggieType, vsubtype: VeggieSubType, page_size: u16, page: u16) -> Vec<GeneJSON>;
+
+    // A page of the genes waiting for a curator.
+    fn get_pending_genes(&self, page_size: u16, page: u16) -> Vec<GeneJSON>;
+}
+
+#[derive(PartialEq, Clone, Copy, Debug, Serialize, BorshDeserialize, BorshSerialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeneStatus {
+    Pending,   // submitted, waiting for a curator
+    Rejected,
+    Published, // new veggies can be made from it
+    Retired,   // no more new veggies
+}
+
+#[derive(PartialEq, Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct Gene {
+    pub vtype: VeggieType,
+    pub vsubtype: VeggieSubType,
+    pub meta_url: String,                 // the artwork's JSON on arweave
+    pub media: Option<String>,            // the image itself
+    pub media_hash: Option<Base64VecU8>,  // sha256 of the image
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub artist: Option<String>,           // artist credit, as it should be displayed
+    pub artist_id: Option<AccountId>,     // where the artist's royalties go
+    pub royalty_bps: u32,
+    pub max_supply: Option<u64>,          // None == unlimited
+    pub price: Option<Balance>,           // in NEAR, to mint this very artwork (see mint_gene_json)
+    pub rarity: Rarity,
+    pub stage: Option<Stage>,             // stage art, if it's for a growth stage
+    pub submitted_by: Option<AccountId>,  // the artist, if they submitted it
+    pub minted: u64,
+    pub status: GeneStatus,
+}
+
+impl Gene {
+    // one of the artworks we launched with.  (we don't know those artists' accounts.)
+    pub fn from_artwork(vtype: VeggieType, vsubtype: VeggieSubType, art: &Artwork) -> Self {
+        Self {
+            vtype,
+            vsubtype,
+            meta_url: art.meta_url.to_string(),
+            media: None,
+            media_hash: None,
+            title: art.title.map(|t| t.to_string()),
+            description: art.description.map(|d| d.to_string()),
+            artist: art.artist.map(|a| a.to_string()),
+            artist_id: None,
+            royalty_bps: 0,
+            max_supply: None,
+            price: None,
+            rarity: art.rarity,
+            stage: None,
+            submitted_by: None,
+            minted: 0,
+            status: GeneStatus::Published,
+        }
+    }
+
+    // a gene as a curator describes it; it's published right away.
+    pub fn from_spec(spec: GeneSpec) -> Self {
+        check_pool(spec.vtype, spec.vsubtype);
+        if let Some(artist_id) = &spec.artist_id {
+            assert!(env::is_valid_account_id(artist_id.as_bytes()), "Artist's account ID is invalid.");
+        }
+        // artists get the standard royalty unless told otherwise
+        let royalty_bps = match (&spec.artist_id, spec.royalty_bps) {
+            (None, _) => 0,
+            (Some(_), None) => ARTIST_ROYALTY_BPS,
+            (Some(_), Some(bps)) => bps,
+        };
+        assert!(royalty_bps <= ONE_HUNDRED_PERCENT_BPS, "Royalty can't be more than 100%.");
+        if spec.stage.is_some() && spec.vtype != vtypes::PLANT {
+            env::panic(b"Only plants have growth stages.")
+        }
+
+        Self {
+            vtype: spec.vtype,
+            vsubtype: spec.vsubtype,
+            meta_url: spec.meta_url,
+            media: spec.media,
+            media_hash: spec.media_hash,
+            title: spec.title,
+            description: spec.description,
+            artist: spec.artist,
+            artist_id: spec.artist_id,
+            royalty_bps,
+            max_supply: spec.max_supply.map(u64::from),
+            price: spec.price.map(Balance::from),
+            rarity: spec.rarity.unwrap_or_default(),
+            stage: spec.stage,
+            submitted_by: None,
+            minted: 0,
+            status: GeneStatus::Published,
+        }
+    }
+
+    // can we make another veggie from this?
+    pub fn is_available(&self) -> bool {
+        self.status == GeneStatus::Published && !matches!(self.max_supply, Some(max) if self.minted >= max)
+    }
+
+    pub fn royalty(&self) -> Royalty {
+        let mut royalty = Royalty::new();
+        if let Some(artist_id) = &self.artist_id {
+            if self.royalty_bps > 0 {
+                royalty.insert(artist_id.clone(), self.royalty_bps);
+            }
+        }
+        royalty
+    }
+}
+
+// (startpoint, limit) of a page; page_size 0 means everything.
+fn page_bounds(len: u64, page_size: u16, page: u16) -> (u64, u64) {
+    if page_size == 0 {
+        (0, len)
+    } else {
+        (page_size as u64 * page as u64, page_size as u64)
+    }
+}
+
+// panic unless genes can be pooled under this type and subtype
+fn check_pool(vtype: VeggieType, vsubtype: VeggieSubType) {
+    if !(vtype == vtypes::PLANT || vtype == vtypes::HARVEST) {
+        panic!("Unknown veggie type {}.", vtype);
+    }
+    if vsubtype == 0 || vsubtype as usize >= P_NAMES.len() {
+        panic!("Unknown plant type {}.", vsubtype);
+    }
+}
+
+// a gene as sent by whoever adds or updates it
+#[derive(Deserialize, Clone, Debug)]
+pub struct GeneSpec {
+    pub vtype: VeggieType,
+    pub vsubtype: VeggieSubType,
+    pub meta_url: String,
+    pub media: Option<String>,
+    pub media_hash: Option<Base64VecU8>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub artist: Option<String>,
+    pub artist_id: Option<AccountId>,
+    pub royalty_bps: Option<u32>, // defaults to ARTIST_ROYALTY_BPS if there's an artist_id
+    pub max_supply: Option<U64>,
+    pub price: Option<U128>,
+    pub rarity: Option<Rarity>, // defaults to common
+    pub stage: Option<Stage>,   // only for stage art
+}
+
+// this is the external, JSON-compatible version for method calls.  (u64s are strings.)
+#[derive(PartialEq, Clone, Debug, Serialize)]
+pub struct GeneJSON {
+    pub gene_id: U64,
+    pub vtype: VeggieType,
+    pub vsubtype: VeggieSubType,
+    pub meta_url: String,
+    pub media: Option<String>,
+    pub media_hash: Option<Base64VecU8>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub artist: Option<String>,
+    pub artist_id: Option<AccountId>,
+    pub royalty_bps: u32,
+    pub max_supply: Option<U64>,
+    pub price: Option<U128>,
+    pub rarity: Rarity,
+    pub stage: Option<Stage>,
+    pub submitted_by: Option<AccountId>,
+    pub minted: U64,
+    pub status: GeneStatus,
+}
+
+impl GeneJSON {
+    pub fn new(gene_id: GeneId, g: Gene) -> Self {
+        Self {
+            gene_id: gene_id.into(),
+            vtype: g.vtype,
+            vsubtype: g.vsubtype,
+            meta_url: g.meta_url,
+            media: g.media,
+            media_hash: g.media_hash,
+            title: g.title,
+            description: g.description,
+            artist: g.artist,
+            artist_id: g.artist_id,
+            royalty_bps: g.royalty_bps,
+            max_supply: g.max_supply.map(U64::from),
+            price: g.price.map(U128::from),
+            rarity: g.rarity,
+            stage: g.stage,
+            submitted_by: g.submitted_by,
+            minted: g.minted.into(),
+            status: g.status,
+        }
+    }
+}
+
+// Pools are short lists of gene IDs, so each is kept whole, in one storage entry.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Genome {
+    genes: Vector<Gene>, // GeneId == index
+    pools: UnorderedMap<PoolKey, Vec<GeneId>>,     // every gene, by pool
+    available: UnorderedMap<TierKey, Vec<GeneId>>, // the genes that can still make veggies, by tier
+    staged: UnorderedMap<StageKey, Vec<GeneId>>,   // the published stage art
+    pending: UnorderedSet<GeneId>,                 // submissions waiting for a curator
+}
+
+impl Genome {
+    pub fn new() -> Self {
+        Self {
+            genes: Vector::new(b"genes".to_vec()),
+            pools: UnorderedMap::new(b"gene-pools".to_vec()),
+            available: UnorderedMap::new(b"genes-available-by-tier".to_vec()),
+            staged: UnorderedMap::new(b"genes-staged".to_vec()),
+            pending: UnorderedSet::new(b"genes-pending".to_vec()),
+        }
+    }
+
+    // a genome with the artworks we launched with
+    pub fn launched() -> Self {
+        let mut genome = Self::new();
+        for (ptype, art) in P_ART.iter() {
+            genome.add(Gene::from_artwork(vtypes::PLANT, *ptype, art));
+        }
+        for (ptype, art) in H_ART.iter() {
+            genome.add(Gene::from_artwork(vtypes::HARVEST, *ptype, art));
+        }
+        genome
+    }
+
+    pub fn get(&self, gene_id: GeneId) -> Gene {
+        match self.genes.get(gene_id) {
+            Some(gene) => gene,
+            None => env::panic(b"Gene does not exist."),
+        }
+    }
+
+    // the gene in a pool with this artwork JSON, if there is one
+    pub fn find(&self, vtype: VeggieType, vsubtype: VeggieSubType, meta_url: &str) -> Option<GeneId> {
+        self.pools.get(&(vtype, vsubtype)).unwrap_or_default().into_iter().find(|gene_id| self.get(*gene_id).meta_url == meta_url)
+    }
+
+    // adds a gene; published ones go in their pool, pending ones wait for a curator.
+    pub fn add(&mut self, gene: Gene) -> GeneId {
+        let gene_id = self.genes.len();
+        self.genes.push(&gene);
+        match gene.status {
+            GeneStatus::Pending => { self.pending.insert(&gene_id); },
+            _ => self.add_to_pool(gene_id, &gene),
+        }
+        gene_id
+    }
+
+    fn add_to_pool(&mut self, gene_id: GeneId, gene: &Gene) {
+        let key = (gene.vtype, gene.vsubtype);
+        let mut pool = self.pools.get(&key).unwrap_or_default();
+        pool.push(gene_id);
+        self.pools.insert(&key, &pool);
+        self.refresh_available(gene_id, gene);
+    }
+
+    // an artist's submission, waiting for a curator
+    pub fn submit(&mut self, spec: GeneSpec, submitted_by: AccountId) -> GeneId {
+        let artist_id = spec.artist_id.clone().unwrap_or_else(|| submitted_by.clone());
+        let mut gene = Gene::from_spec(GeneSpec { price: None, artist_id: Some(artist_id), ..spec });
+        gene.submitted_by = Some(submitted_by);
+        gene.status = GeneStatus::Pending;
+        self.add(gene)
+    }
+
+    // the gene, if it's waiting for a curator
+    fn get_pending(&self, gene_id: GeneId) -> Gene {
+        let gene = self.get(gene_id);
+        if gene.status != GeneStatus::Pending {
+            env::panic(b"Gene is not pending.")
+        }
+        gene
+    }
+
+    pub fn approve(&mut self, gene_id: GeneId, price: Balance, max_supply: Option<u64>) {
+        let mut gene = self.get_pending(gene_id);
+        gene.price = Some(price);
+        gene.max_supply = max_supply;
+        gene.status = GeneStatus::Published;
+        self.genes.replace(gene_id, &gene);
+        self.pending.remove(&gene_id);
+        self.add_to_pool(gene_id, &gene);
+    }
+
+    pub fn reject(&mut self, gene_id: GeneId) {
+        let mut gene = self.get_pending(gene_id);
+        gene.status = GeneStatus::Rejected;
+        self.genes.replace(gene_id, &gene);
+        self.pending.remove(&gene_id);
+    }
+
+    pub fn update(&mut self, gene_id: GeneId, spec: GeneSpec) {
+        let old = self.get(gene_id);
+        if (old.vtype, old.vsubtype) != (spec.vtype, spec.vsubtype) {
+            env::panic(b"A gene can't change its veggie type; retire it and add a new one.")
+        }
+        let mut gene = Gene::from_spec(spec);
+        if matches!(gene.max_supply, Some(max) if max < old.minted) {
+            env::panic(b"Max supply can't be less than the number already minted.")
+        }
+        gene.minted = old.minted;
+        gene.submitted_by = old.submitted_by.clone();
+        gene.status = old.status;
+        self.genes.replace(gene_id, &gene);
+        if (gene.rarity, gene.stage) != (old.rarity, old.stage) {
+            self.list(gene_id, &old, false);
+        }
+        self.refresh_available(gene_id, &gene);
+    }
+
+    pub fn retire(&mut self, gene_id: GeneId) {
+        let mut gene = self.get(gene_id);
+        if gene.status != GeneStatus::Published {
+            env::panic(b"Only published genes can be retired.")
+        }
+        gene.status = GeneStatus::Retired;
+        self.genes.replace(gene_id, &gene);
+        self.refresh_available(gene_id, &gene);
+    }
+
+    pub fn page(&self, vtype: VeggieType, vsubtype: VeggieSubType, page_size: u16, page: u16) -> Vec<GeneJSON> {
+        let pool = self.pools.get(&(vtype, vsubtype)).unwrap_or_default();
+        let (startpoint, limit) = page_bounds(pool.len() as u64, page_size, page);
+        pool.into_iter().skip(startpoint as usize).take(limit as usize).map(|id| GeneJSON::new(id, self.get(id))).collect()
+    }
+
+    pub fn pending_page(&self, page_size: u16, page: u16) -> Vec<GeneJSON> {
+        let pending = self.pending.as_vector();
+        let (startpoint, limit) = page_bounds(pending.len(), page_size, page);
+        page_of(pending, startpoint, limit).into_iter().map(|id| GeneJSON::new(id, self.get(id))).collect()
+    }
+
+    // whether anyone has made art for this type of veggie, even if it's all sold out.
+    // (stage art doesn't count: it's never minted.)
+    pub fn has_art(&self, vtype: VeggieType, vsubtype: VeggieSubType) -> bool {
+        self.pools.get(&(vtype, vsubtype)).is_some_and(|pool| pool.iter().any(|gene_id| self.get(*gene_id).stage.is_none()))
+    }
+
+    // the art for plants of this type at this stage
+    pub fn stage_art(&self, vtype: VeggieType, vsubtype: VeggieSubType, stage: Stage) -> Vec<GeneId> {
+        self.staged.get(&(vtype, vsubtype, stage)).unwrap_or_default()
+    }
+
+    // the genes in a pool that can still make veggies
+    pub fn available(&self, vtype: VeggieType, vsubtype: VeggieSubType) -> Vec<GeneId> {
+        TIERS.iter().flat_map(|rarity| self.available_in(vtype, vsubtype, *rarity)).collect()
+    }
+
+    pub fn available_in(&self, vtype: VeggieType, vsubtype: VeggieSubType, rarity: Rarity) -> Vec<GeneId> {
+        self.available.get(&(vtype, vsubtype, rarity)).unwrap_or_default()
+    }
+
+    // The genes to draw from for a veggie of the given tier.  If that tier has run out,
+    // the next rarest tier that hasn't stands in for it (or failing that, the next less rare).
+    pub fn available_nearest(&self, vtype: VeggieType, vsubtype: VeggieSubType, rarity: Rarity) -> Vec<GeneId> {
+        let (below, above) = TIERS.split_at(TIERS.iter().position(|r| *r == rarity).unwrap() + 1);
+        below.iter().rev().chain(above.iter())
+            .map(|r| self.available_in(vtype, vsubtype, *r))
+            .find(|genes| !genes.is_empty())
+            .unwrap_or_default()
+    }
+
+    // count a new veggie made from this gene; returns the gene.
+    pub fn record_mint(&mut self, gene_id: GeneId) -> Gene {
+        let mut gene = self.get(gene_id);
+        if !gene.is_available() {
+            env::panic(b"Gene is not published, or sold out.")
+        }
+        gene.minted += 1;
+        self.genes.replace(gene_id, &gene);
+        self.refresh_available(gene_id, &gene);
+        gene
+    }
+
+    // keep the gene in its tier's (or stage's) available list if and only if it's available
+    fn refresh_available(&mut self, gene_id: GeneId, gene: &Gene) {
+        self.list(gene_id, gene, gene.is_available());
+    }
+
+    fn list(&mut self, gene_id: GeneId, gene: &Gene, available: bool) {
+        match gene.stage {
+            None => list_in(&mut self.available, &(gene.vtype, gene.vsubtype, gene.rarity), gene_id, available),
+            Some(stage) => list_in(&mut self.staged, &(gene.vtype, gene.vsubtype, stage), gene_id, available),
+        }
+    }
+}
+
+// put the gene on (or take it off) one of the genome's lists
+fn list_in<K: BorshSerialize + BorshDeserialize>(lists: &mut UnorderedMap<K, Vec<GeneId>>, key: &K, gene_id: GeneId, available: bool) {
+    let mut list = lists.get(key).unwrap_or_default();
+    let listed = list.contains(&gene_id);
+    if available && !listed {
+        list.push(gene_id);
+    } else if !available && listed {
+        list.retain(|id| *id != gene_id);
+    } else {
+        return;
+    }
+    lists.insert(key, &list);
+}
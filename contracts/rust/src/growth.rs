@@ -0,0 +1,135 @@
+//! How plants grow.  A plant starts out as a seed when it's planted (minted),
+//! sprouts, and from then on goes through seasons: it matures, flowers, wilts,
+//! and sprouts again.  How fast depends on the growth rate in its DNA.
+//! Plants can only be harvested while mature or flowering.  Each time round, from
+//! sprouting to wilting, is a season.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::dna::Traits;
+
+pub const HOUR: u64 = 60 * 60 * 1_000_000_000; // block timestamps are in nanoseconds
+const MINUTE: u64 = HOUR / 60;
+
+// how long until `then`, in minutes (rounded up), for telling people how long to wait
+pub fn minutes_until(then: u64, now: u64) -> u64 {
+    then.saturating_sub(now).div_ceil(MINUTE)
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Seed,
+    Sprout,
+    Mature,
+    Flowering,
+    Wilting,
+}
+
+impl Stage {
+    pub fn can_harvest(self) -> bool {
+        matches!(self, Stage::Mature | Stage::Flowering)
+    }
+
+    // for error messages: "while it's ..."
+    pub fn describe(self) -> &'static str {
+        match self {
+            Stage::Seed => "a seed",
+            Stage::Sprout => "a sprout",
+            Stage::Mature => "mature",
+            Stage::Flowering => "flowering",
+            Stage::Wilting => "wilting",
+        }
+    }
+}
+
+// how long each stage lasts for a plant of average growth rate
+const SEED_TIME: u64 = HOUR;
+const SEASON: [(Stage, u64); 4] = [
+    (Stage::Sprout, 12 * HOUR),
+    (Stage::Mature, 48 * HOUR),
+    (Stage::Flowering, 48 * HOUR),
+    (Stage::Wilting, 24 * HOUR),
+];
+
+// the slowest growers (growth 0) take twice as long as average; the fastest (15) about 2/3 as long.
+fn scaled(time: u64, growth: u8) -> u64 {
+    time * 16 / (8 + growth as u64)
+}
+
+fn season_length(growth: u8) -> u64 {
+    SEASON.iter().map(|(_, time)| scaled(*time, growth)).sum()
+}
+
+// what stage a plant planted at `planted_at` is at, at `now`
+pub fn stage(traits: &Traits, planted_at: u64, now: u64) -> Stage {
+    let age = now.saturating_sub(planted_at);
+    let seed_time = scaled(SEED_TIME, traits.growth);
+    if age < seed_time {
+        return Stage::Seed;
+    }
+    let mut into_season = (age - seed_time) % season_length(traits.growth);
+    for (stage, time) in SEASON.iter() {
+        let time = scaled(*time, traits.growth);
+        if into_season < time {
+            return *stage;
+        }
+        into_season -= time;
+    }
+    Stage::Wilting
+}
+
+// which season a plant is in at `now`, counting from 0.  (a seed counts as being in its first season.)
+pub fn season(traits: &Traits, planted_at: u64, now: u64) -> u64 {
+    let age = now.saturating_sub(planted_at);
+    age.saturating_sub(scaled(SEED_TIME, traits.growth)) / season_length(traits.growth)
+}
+
+// when the plant next becomes mature, after the season it's in at `now`
+pub fn next_season_at(traits: &Traits, planted_at: u64, now: u64) -> u64 {
+    let next = season(traits, planted_at, now) + 1;
+    planted_at + scaled(SEED_TIME, traits.growth) + next * season_length(traits.growth) + scaled(SEASON[0].1, traits.growth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn growth(rate: u8) -> Traits {
+        Traits::from_dna((rate as u64) << 30)
+    }
+
+    #[test]
+    fn average_plant() {
+        let t = growth(8);
+        assert_eq!(Stage::Seed, stage(&t, 0, 0));
+        assert_eq!(Stage::Sprout, stage(&t, 0, HOUR));
+        assert_eq!(Stage::Mature, stage(&t, 0, 13 * HOUR));
+        assert_eq!(Stage::Flowering, stage(&t, 0, 61 * HOUR));
+        assert_eq!(Stage::Wilting, stage(&t, 0, 109 * HOUR));
+        assert_eq!(Stage::Sprout, stage(&t, 0, 133 * HOUR), "should sprout again after wilting");
+        assert_eq!(Stage::Seed, stage(&t, 5 * HOUR, 5 * HOUR), "should count from planting");
+    }
+
+    #[test]
+    fn seasons() {
+        let t = growth(8);
+        assert_eq!(0, season(&t, 0, 0));
+        assert_eq!(0, season(&t, 0, 132 * HOUR));
+        assert_eq!(1, season(&t, 0, 133 * HOUR), "new season should start at sprouting");
+        assert_eq!(145 * HOUR, next_season_at(&t, 0, 61 * HOUR), "should be mature again after sprouting");
+        assert_eq!(Stage::Mature, stage(&t, 0, next_season_at(&t, 0, 61 * HOUR)));
+        assert_eq!(150 * HOUR, next_season_at(&t, 5 * HOUR, 61 * HOUR), "should count from planting");
+    }
+
+    #[test]
+    fn growth_rate() {
+        assert_eq!(Stage::Seed, stage(&growth(0), 0, HOUR), "slow plant should still be a seed");
+        assert_eq!(Stage::Sprout, stage(&growth(15), 0, HOUR), "fast plant should have sprouted");
+        // a day and a bit in, every plant is mature
+        for rate in 0..16 {
+            assert_eq!(Stage::Mature, stage(&growth(rate), 0, 30 * HOUR), "growth rate {} isn't mature", rate);
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! How often a plant can be harvested.  Each plant type has a cooldown between
+//! harvests and a number of harvests it gives per growth season (see H_POLICY);
+//! a plant of average vigor gets exactly those, a vigorous one recovers faster
+//! and gives more, and a weak one slower and fewer.
+
+use crate::constants::HarvestPolicy;
+use crate::dna::Traits;
+
+// vigor that gets a plant type's limits as they are
+const AVERAGE_VIGOR: u64 = 128;
+// ... and how much vigor counts: from 3/4 as much as average (vigor 0) to about 5/4 (vigor 255)
+const BASE: u64 = 4 * AVERAGE_VIGOR;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Limits {
+    pub cooldown: u64, // nanoseconds
+    pub per_season: u32,
+}
+
+pub fn limits(policy: &HarvestPolicy, traits: &Traits) -> Limits {
+    let vigor = traits.vigor as u64;
+    let scale = BASE - AVERAGE_VIGOR + vigor;
+    Limits {
+        cooldown: policy.cooldown * BASE / scale,
+        // rounded, but a harvestable plant always gets at least one
+        per_season: ((policy.per_season as u64 * scale + BASE / 2) / BASE).max(policy.per_season.min(1) as u64) as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ptypes;
+    use crate::growth::HOUR;
+
+    fn vigor(v: u8) -> Traits {
+        Traits::from_dna(v as u64)
+    }
+
+    #[test]
+    fn average_vigor() {
+        let policy = HarvestPolicy::yields(ptypes::ORACLE, 5).limited(6, 3);
+        assert_eq!(Limits { cooldown: 6 * HOUR, per_season: 3 }, limits(&policy, &vigor(128)));
+    }
+
+    #[test]
+    fn vigor_matters() {
+        let policy = HarvestPolicy::yields(ptypes::ORACLE, 5).limited(8, 4);
+        let weak = limits(&policy, &vigor(0));
+        let strong = limits(&policy, &vigor(255));
+        assert!(weak.cooldown > 8 * HOUR && strong.cooldown < 8 * HOUR, "vigor should change the cooldown");
+        assert_eq!((3, 5), (weak.per_season, strong.per_season), "vigor should change the yield");
+
+        let seed = HarvestPolicy::yields(ptypes::SEED, 50).limited(24, 1);
+        assert_eq!(1, limits(&seed, &vigor(0)).per_season, "weak plants should still give something");
+        assert_eq!(0, limits(&HarvestPolicy::none(), &vigor(255)).per_season);
+    }
+}
@@ -7,12 +7,14 @@
 /// Implements blockchain ledger for plants and their fruit
 ///
 
-use near_sdk::{env, near_bindgen, AccountId, Balance, json_types};
-use near_sdk::collections::UnorderedMap;
+use near_sdk::{env, near_bindgen, AccountId, Balance, Promise, PromiseOrValue, json_types};
+use near_sdk::collections::{UnorderedMap, UnorderedSet, Vector};
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::serde::Serialize;
 
+use std::collections::HashMap;
+
 use rand::prelude::*;
 use rand_chacha::ChaCha8Rng;
 use rand_seeder::{Seeder};
@@ -21,10 +23,50 @@ use rand_seeder::{Seeder};
 static ALLOC: near_sdk::wee_alloc::WeeAlloc<'_> = near_sdk::wee_alloc::WeeAlloc::INIT;
 
 mod token_bank;
-use token_bank::{NEP4, TokenBank, TokenSet, TokenId};
+use token_bank::{NEP4, NEP171, NEP171Resolver, NEP178, NEP181, NFTokenJSON, TokenBank, TokenSet, TokenId, TokenJSON, page_bounds, page_of};
 
 mod constants;
-use constants::{VeggieType, VeggieSubType, vtypes, P_POOL, H_POOL, P_PRICES, H_PRICES};
+use constants::{VeggieType, VeggieSubType, PlantType, vtypes, ptypes, H_POLICY, HarvestPolicy, P_NAMES, H_NAMES, MAX_INFLUENCERS, MAX_NICKNAME, BREED_COOLDOWN, STORAGE_PRICE_PER_BYTE};
+
+mod metadata;
+use metadata::{NEP177, NFTContractMetadata, TokenMetadata};
+
+mod payout;
+use payout::{NEP199, Payout, Royalty};
+
+mod events;
+
+mod genome;
+use genome::{Genes, Gene, GeneId, GeneJSON, GeneSpec, Genome, NO_GENE};
+
+mod dna;
+use dna::Traits;
+
+mod rarity;
+use rarity::{Odds, Rarity, TierOdds, ALL_ODDS};
+
+mod growth;
+use growth::Stage;
+
+mod care;
+use care::{Care, PlantStatus};
+
+mod harvest;
+
+mod text;
+
+mod lucky;
+use lucky::LuckyDraw;
+
+mod render;
+
+mod names;
+
+mod prices;
+use prices::{check_price, PriceList, Prices, TypeOdds};
+
+mod migration;
+use migration::OldPlantaryContract;
 
 ///
 /// the veggie section
@@ -38,48 +80,158 @@ pub struct Veggie {
     pub vtype: VeggieType,
     pub vsubtype: VeggieSubType,
     pub parent: TokenId,
+    pub influencers: Vec<TokenId>, // other plants that helped grow a harvest
+    pub co_parent: TokenId,        // a bred plant's other parent
+    pub generation: u32,           // 0 for plants minted from nothing; one more than its older parent for bred ones
+    pub breed_ready_at: u64,       // block timestamp when a plant can breed again
+    pub planted_at: u64,           // block timestamp when it was minted (or harvested)
+    pub hydration: u8,             // a plant's care, as of cared_at (see care.rs)
+    pub health: u8,
+    pub cared_at: u64,
+    pub harvested_at: u64,         // block timestamp of a plant's last harvest (0 if never)
+    pub harvest_season: u64,       // the growth season it was in then (see growth.rs) ...
+    pub season_harvests: u32,      // ... and how many times it was harvested in that season
     pub dna: u64,
     pub meta_url: String,
+    pub metadata: TokenMetadata,
+    pub royalty: Royalty,
+    pub gene_id: GeneId,
+    pub rarity: Rarity,
+    pub text: Option<String>,      // a harvest's message, made from its DNA (see text.rs)
+    pub lucky: Option<LuckyDraw>,  // a Lucky Numbers harvest's numbers
+    pub name: Option<String>,      // a plant's species name, made from its DNA (see names.rs); also its title
+    pub nickname: Option<String>,  // what its owner calls it
 }
 
 impl Veggie {
-    pub fn new(vid: TokenId, parent_vid: TokenId, vtype: VeggieType, vsubtype:VeggieSubType, dna: u64, meta_url: &String) -> Self {
+    pub fn new(vid: TokenId, parent_vid: TokenId, vtype: VeggieType, vsubtype:VeggieSubType, dna: u64, meta_url: &String, metadata: TokenMetadata) -> Self {
 
         Self {
             vid: vid,
             vtype: vtype,           // plant or harvest 
             vsubtype: vsubtype,
             parent: parent_vid,
+            influencers: Vec::new(),
+            co_parent: 0,
+            generation: 0,
+            breed_ready_at: 0,
+            planted_at: 0,
+            hydration: care::FULL,
+            health: care::FULL,
+            cared_at: 0,
+            harvested_at: 0,
+            harvest_season: 0,
+            season_harvests: 0,
             dna: dna,
             meta_url: meta_url.to_string(),
-            // rarity ...
+            metadata,
+            royalty: Royalty::new(),
+            gene_id: 0,
+            rarity: Rarity::Common,
+            text: None,
+            lucky: None,
+            name: None,
+            nickname: None,
         }
     }
+
+    // plants grow; harvests don't
+    pub fn stage(&self) -> Option<Stage> {
+        if self.vtype != vtypes::PLANT {
+            return None;
+        }
+        Some(growth::stage(&Traits::from_dna(self.dna), self.planted_at, env::block_timestamp()))
+    }
+
+    // how the veggie is doing now
+    pub fn care(&self) -> Care {
+        Care { hydration: self.hydration, health: self.health }.after(env::block_timestamp().saturating_sub(self.cared_at))
+    }
+
+    pub fn set_care(&mut self, care: Care) {
+        self.hydration = care.hydration;
+        self.health = care.health;
+        self.cared_at = env::block_timestamp();
+    }
+
+    // how many times the plant has been harvested in the season it's in now
+    pub fn harvests_this_season(&self) -> u32 {
+        let season = growth::season(&Traits::from_dna(self.dna), self.planted_at, env::block_timestamp());
+        if self.vtype != vtypes::PLANT || season != self.harvest_season {
+            return 0;
+        }
+        self.season_harvests
+    }
 }
 
 // this is the external, JSON-compatible version for method calls.  (u64s are strings.)
 
-pub type TokenJSON = json_types::U64;
-
 #[derive(PartialEq, Clone, Debug, Serialize, BorshDeserialize, BorshSerialize)]
 pub struct VeggieJSON {
     pub vid: TokenJSON,
     pub vtype: VeggieType,
     pub vsubtype: VeggieSubType,
     pub parent: TokenJSON,
+    pub influencers: Vec<TokenJSON>,
+    pub co_parent: TokenJSON,
+    pub generation: u32,
+    pub breed_ready_at: json_types::U64,
+    pub planted_at: json_types::U64,
+    pub hydration: u8,
+    pub health: u8,
+    pub cared_at: json_types::U64,
+    pub harvested_at: json_types::U64,
+    pub harvest_season: json_types::U64,
+    pub season_harvests: u32,
     pub dna: json_types::U64,
     pub meta_url: String,
+    pub metadata: TokenMetadata,
+    pub royalty: Royalty,
+    pub gene_id: json_types::U64,
+    pub rarity: Rarity,
+    pub text: Option<String>,
+    pub lucky: Option<LuckyDraw>,
+    pub name: Option<String>,
+    pub nickname: Option<String>,
+    // decoded from the DNA, for convenience
+    pub traits: Traits,
+    // a plant's growth stage, as of now, and the art for it (if there is any)
+    pub stage: Option<Stage>,
+    pub stage_meta_url: Option<String>,
 }
 
 impl From<Veggie> for VeggieJSON {
     fn from(v: Veggie) -> Self {
+        let stage = v.stage();
         Self {
             vid: v.vid.into(),
             vtype: v.vtype,
             vsubtype: v.vsubtype,
             parent: v.parent.into(),
+            influencers: v.influencers.into_iter().map(|id| id.into()).collect(),
+            co_parent: v.co_parent.into(),
+            generation: v.generation,
+            breed_ready_at: v.breed_ready_at.into(),
+            planted_at: v.planted_at.into(),
+            hydration: v.hydration,
+            health: v.health,
+            cared_at: v.cared_at.into(),
+            harvested_at: v.harvested_at.into(),
+            harvest_season: v.harvest_season.into(),
+            season_harvests: v.season_harvests,
             dna: v.dna.into(),
-            meta_url: v.meta_url
+            meta_url: v.meta_url,
+            metadata: v.metadata,
+            royalty: v.royalty,
+            gene_id: v.gene_id.into(),
+            rarity: v.rarity,
+            text: v.text,
+            lucky: v.lucky,
+            name: v.name,
+            nickname: v.nickname,
+            traits: Traits::from_dna(v.dna),
+            stage,
+            stage_meta_url: None,
         }
     }
 }
@@ -92,24 +244,80 @@ impl From<VeggieJSON> for Veggie {
             vtype: v.vtype,
             vsubtype: v.vsubtype,
             parent: v.parent.into(),
+            influencers: v.influencers.into_iter().map(|id| id.into()).collect(),
+            co_parent: v.co_parent.into(),
+            generation: v.generation,
+            breed_ready_at: v.breed_ready_at.into(),
+            planted_at: v.planted_at.into(),
+            hydration: v.hydration,
+            health: v.health,
+            cared_at: v.cared_at.into(),
+            harvested_at: v.harvested_at.into(),
+            harvest_season: v.harvest_season.into(),
+            season_harvests: v.season_harvests,
             dna: v.dna.into(),
             meta_url: v.meta_url,
+            metadata: v.metadata,
+            royalty: v.royalty,
+            gene_id: v.gene_id.into(),
+            rarity: v.rarity,
+            text: v.text,
+            lucky: v.lucky,
+            name: v.name,
+            nickname: v.nickname,
         }
     }
 }
 
 pub trait Veggies {
     fn get_veggie_json(&self, vid_json: TokenJSON) -> VeggieJSON;
+    fn get_veggie_traits(&self, vid_json: TokenJSON) -> Traits;
+    // the chances of each rarity tier, for the next harvest of a plant
+    fn get_harvest_odds(&self, vid_json: TokenJSON) -> Vec<TierOdds>;
+    // where a veggie came from: its parent, its parent's parent, and so on back to a minted plant
+    // (or to an ancestor that's been burned)
+    fn get_lineage(&self, vid_json: TokenJSON) -> Vec<TokenJSON>;
+    // a picture of the veggie as it is now, drawn from its DNA (and growth stage)
+    fn get_veggie_svg(&self, vid_json: TokenJSON) -> String;
+
+    // give one of your veggies a nickname (of up to MAX_NICKNAME characters), or "" to take it away
+    fn set_nickname(&mut self, vid_json: TokenJSON, nickname: String) -> VeggieJSON;
+    // draw lucky numbers again, to check a Lucky Numbers harvest's: `verify_lucky_numbers` does it
+    // for a harvest, with what it recorded, and `draw_lucky_numbers` with whatever you give it.
+    fn verify_lucky_numbers(&self, vid_json: TokenJSON) -> bool;
+    fn draw_lucky_numbers(&self, dna: json_types::U64, seed: json_types::Base64VecU8, count: u8, max: u32) -> Vec<u32>;
     fn count_owner_veggies(&self, owner_id: AccountId, vtype: VeggieType) -> u64;
     fn get_owner_veggies_page_json(&self, owner_id: AccountId, vtype: VeggieType, page_size: u16, page: u16) -> Vec<VeggieJSON>;
+    // the same, paged by veggie id: up to `limit` (0 == all) of the owner's veggies after `after`.
+    // Pass the last vid of each page as the next `after` to see every veggie once, however many come and go in between.
+    fn get_owner_veggies_after(&self, owner_id: AccountId, vtype: VeggieType, after: Option<TokenJSON>, limit: u16) -> Vec<VeggieJSON>;
 
     fn mint_plant_json(&mut self, 
                     vsubtype: VeggieSubType,
                     )->VeggieJSON;
 
+    // mint a plant of a type drawn at random (see `get_random_plant_odds`), at the random-plant price.
+    // The plant's vsubtype says which type you got.
+    fn mint_random_plant(&mut self) -> VeggieJSON;
+
+    // mint a plant of one particular published artwork, at the price its curator set.
+    fn mint_gene_json(&mut self, gene_id: json_types::U64) -> VeggieJSON;
+
     fn delete_veggie_json(&mut self, vid_json: TokenJSON);
 
     fn harvest_plant_json(&mut self, parent_id: TokenJSON) -> VeggieJSON;
+
+    // harvest the primary plant, with other plants of yours lending their DNA to the harvest's rarity and traits.
+    // (priced like a harvest of the primary plant.)
+    fn harvest_with_plants(&mut self, primary: TokenJSON, influencers: Vec<TokenJSON>) -> VeggieJSON;
+
+    // cross two of your plants of the same type to get a new one, of that type.  Both then need a rest.
+    fn breed_plants(&mut self, a: TokenJSON, b: TokenJSON) -> VeggieJSON;
+
+    // look after your plant: water fills it up, fertilizer (which costs) brings back some health.
+    fn water_plant(&mut self, vid: TokenJSON) -> PlantStatus;
+    fn fertilize_plant(&mut self, vid: TokenJSON) -> PlantStatus;
+    fn get_plant_status(&self, vid: TokenJSON) -> PlantStatus;
 }
 
 // public veggies implementation
@@ -137,7 +345,15 @@ impl Veggies for PlantaryContract {
     }
 
     fn get_veggie_json(&self, vid: TokenJSON) -> VeggieJSON {
-        self.get_veggie(vid.into()).into()
+        self.veggie_json(self.get_veggie(vid.into()))
+    }
+
+    fn get_veggie_traits(&self, vid: TokenJSON) -> Traits {
+        Traits::from_dna(self.get_veggie(vid.into()).dna)
+    }
+
+    fn get_harvest_odds(&self, vid: TokenJSON) -> Vec<TierOdds> {
+        rarity::odds_json(&self.harvest_odds(&self.get_veggie(vid.into())))
     }
 
     fn delete_veggie_json(&mut self, vid: TokenJSON){
@@ -149,25 +365,144 @@ impl Veggies for PlantaryContract {
         // confirm that we were paid the right amount:
         let parent_id = TokenId::from(parent_id_json);
         let parent = self.get_veggie(parent_id);
-        self.paid_up(H_PRICES[parent.vsubtype as usize]);
+        self.paid_up(self.harvest_price(&parent));
+
+        let h = self.harvest_plant(parent_id);
+        self.veggie_json(h)
+    }
+
+    #[payable]
+    fn harvest_with_plants(&mut self, primary: TokenJSON, influencers: Vec<TokenJSON>) -> VeggieJSON {
+        let parent = self.get_veggie(primary.into());
+        self.paid_up(self.harvest_price(&parent));
+
+        let influencers: Vec<TokenId> = influencers.into_iter().map(TokenId::from).collect();
+        let h = self.harvest_plants(parent.vid, &influencers);
+        self.veggie_json(h)
+    }
+
+    #[payable]
+    fn breed_plants(&mut self, a: TokenJSON, b: TokenJSON) -> VeggieJSON {
+        self.paid_up(self.prices.breed);
+        let child = self.breed(a.into(), b.into());
+        self.veggie_json(child)
+    }
+
+    fn water_plant(&mut self, vid: TokenJSON) -> PlantStatus {
+        self.tend(vid.into(), Care::watered)
+    }
+
+    #[payable]
+    fn fertilize_plant(&mut self, vid: TokenJSON) -> PlantStatus {
+        self.paid_up(self.prices.fertilizer);
+        self.tend(vid.into(), Care::fertilized)
+    }
+
+    fn get_plant_status(&self, vid: TokenJSON) -> PlantStatus {
+        self.plant_status(&self.get_plant(vid.into()))
+    }
+
+    fn verify_lucky_numbers(&self, vid: TokenJSON) -> bool {
+        let h = self.get_veggie(vid.into());
+        match h.lucky {
+            Some(draw) => draw.numbers == lucky::draw(h.dna, &draw.seed.0, draw.count, draw.max),
+            None => env::panic(b"Not a Lucky Numbers harvest."),
+        }
+    }
+
+    fn draw_lucky_numbers(&self, dna: json_types::U64, seed: json_types::Base64VecU8, count: u8, max: u32) -> Vec<u32> {
+        lucky::draw(dna.into(), &seed.0, count, max)
+    }
+
+    fn set_nickname(&mut self, vid: TokenJSON, nickname: String) -> VeggieJSON {
+        let mut v = self.get_veggie(vid.into());
+        if self.token_bank.get_token_owner(v.vid) != env::predecessor_account_id() {
+            env::panic(b"You can only nickname veggies you own.");
+        }
+        if nickname.chars().count() > MAX_NICKNAME {
+            panic!("Nicknames can be at most {} characters.", MAX_NICKNAME);
+        }
+        v.nickname = if nickname.is_empty() { None } else { Some(nickname) };
+        self.veggies.insert(&v.vid, &v);
+        self.veggie_json(v)
+    }
+
+    fn get_veggie_svg(&self, vid: TokenJSON) -> String {
+        self.veggie_svg(&self.get_veggie(vid.into()))
+    }
 
-        self.harvest_plant(parent_id).into()
+    fn get_lineage(&self, vid: TokenJSON) -> Vec<TokenJSON> {
+        let mut lineage = Vec::new();
+        let mut parent = self.get_veggie(vid.into()).parent;
+        while parent != 0 {
+            lineage.push(parent.into());
+            parent = match self.veggies.get(&parent) {
+                Some(v) => v.parent,
+                None => 0,
+            };
+        }
+        lineage
     }
 
     fn get_owner_veggies_page_json(&self, owner_id: AccountId, vtype: VeggieType, page_size: u16, page: u16) -> Vec<VeggieJSON> {
-        self.get_owner_veggies_page(owner_id, vtype, page_size, page).iter().map(|v| VeggieJSON::from(v.clone())).collect()
+        self.get_owner_veggies_page(owner_id, vtype, page_size, page).into_iter().map(|v| self.veggie_json(v)).collect()
+    }
+
+    fn get_owner_veggies_after(&self, owner_id: AccountId, vtype: VeggieType, after: Option<TokenJSON>, limit: u16) -> Vec<VeggieJSON> {
+        self.check_vtype(vtype);
+        let vids = self.token_bank.owner_tokens_after(&owner_id, after.map(TokenId::from));
+        self.veggies_after(vids, vtype, limit_or_all(limit)).into_iter().map(|v| self.veggie_json(v)).collect()
     }
 
     #[payable]
     fn mint_plant_json(&mut self, vsubtype: VeggieSubType) -> VeggieJSON {
         // TODO: only putting this here for now because I haven't figured out how to unit test payments properly ...
         // confirm that we were paid the right amount
-        self.paid_up(P_PRICES[vsubtype as usize]);
-        self.mint_plant(vsubtype).into()
+        self.paid_up(self.prices.plant(vsubtype));
+        let p = self.mint_plant(vsubtype);
+        self.veggie_json(p)
+    }
+
+    #[payable]
+    fn mint_random_plant(&mut self) -> VeggieJSON {
+        self.paid_up(self.prices.random);
+        let ptype = self.random_plant_type();
+        env::log(format!("Your random plant is a {}.", P_NAMES[ptype as usize]).as_bytes());
+        let p = self.mint_plant(ptype);
+        self.veggie_json(p)
+    }
+
+    #[payable]
+    fn mint_gene_json(&mut self, gene_id: json_types::U64) -> VeggieJSON {
+        let gene_id = GeneId::from(gene_id);
+        let gene = self.genome.get(gene_id);
+        if gene.vtype != vtypes::PLANT {
+            env::panic(b"Only plant artwork can be minted.")
+        }
+        if gene.stage.is_some() {
+            env::panic(b"Stage art can't be minted.")
+        }
+        match gene.price {
+            Some(price) => self.paid_up(price),
+            None => env::panic(b"This artwork has no price."),
+        }
+        let p = self.create_veggie_from(vtypes::PLANT, gene.vsubtype, &[], GenePick::Exactly(gene_id));
+        self.veggie_json(p)
     }
 
 }
 
+fn limit_or_all(limit: u16) -> usize {
+    if limit == 0 { usize::MAX } else { limit as usize }
+}
+
+// how create_veggie picks a veggie's artwork from the genome
+enum GenePick {
+    Exactly(GeneId), // this one
+    Any,             // any available in the pool, all equally likely
+    Tiered(Odds),    // draw a rarity tier first, then any available in it
+}
+
 ////////////////////////
 // private methods used by Veggies
 //
@@ -191,8 +526,8 @@ impl PlantaryContract {
 
         // delete from global list
         self.veggies.remove(&vid);
-        // remove from ownership (should use burn_token)
-        self.token_bank.token_to_account.remove(&vid);
+        // remove from ownership (it's ours to burn, whoever owns it)
+        self.token_bank.remove_token(vid);
     }
 
     fn mint_plant(&mut self,
@@ -207,47 +542,264 @@ impl PlantaryContract {
     // harvest_plant() here, a plant veggie gives birth to a harvest veggie
     // (harvest in this case is a verb.)
     fn harvest_plant(&mut self, parent_id: TokenId) -> Veggie {
-        // Assert: user owns this plant
-        // Assert: this type of plant can even have a harvest
-        // Assert: correct money was paid
-        
+        self.harvest_plants(parent_id, &[])
+    }
+
+    // ... with the help of other plants.  The caller must own them all.
+    // (callers must see that it's paid for.)
+    fn harvest_plants(&mut self, parent_id: TokenId, influencer_ids: &[TokenId]) -> Veggie {
         let parent = self.get_veggie(parent_id);
+        let caller = env::predecessor_account_id();
+        if self.token_bank.get_token_owner(parent_id) != caller {
+            env::panic(b"You can only harvest plants you own.");
+        }
+        let policy = self.harvest_policy(&parent);
 
-        // Assert: parent is a plant
-        if parent.vtype != vtypes::PLANT {
-            env::panic(b"non-plant harvest");
+        if influencer_ids.len() > MAX_INFLUENCERS {
+            panic!("At most {} plants can help with a harvest.", MAX_INFLUENCERS);
+        }
+        let mut plants = vec![parent];
+        for vid in influencer_ids {
+            if plants.iter().any(|p| p.vid == *vid) {
+                env::panic(b"Each plant can only help once.");
+            }
+            plants.push(self.get_veggie(*vid));
+        }
+        let (parent, influencers) = plants.split_first().unwrap();
+        for p in influencers {
+            if p.vtype != vtypes::PLANT {
+                env::panic(b"Only plants can help with a harvest.");
+            }
+            if self.token_bank.get_token_owner(p.vid) != caller {
+                env::panic(b"You can only harvest with plants you own.");
+            }
+        }
+        let stage = parent.stage().unwrap();
+        if !stage.can_harvest() {
+            panic!("This plant can't be harvested while it's {}; wait until it's mature.", stage.describe());
+        }
+        let now = env::block_timestamp();
+        let traits = Traits::from_dna(parent.dna);
+        let limits = harvest::limits(policy, &traits);
+        let harvests = parent.harvests_this_season();
+        if harvests >= limits.per_season {
+            let next_season = growth::next_season_at(&traits, parent.planted_at, now);
+            panic!("This plant has given all {} of its harvests this season; it can be harvested again in {} minutes.", limits.per_season, growth::minutes_until(next_season, now));
+        }
+        if parent.harvested_at > 0 && now < parent.harvested_at + limits.cooldown {
+            panic!("This plant can be harvested again in {} minutes.", growth::minutes_until(parent.harvested_at + limits.cooldown, now));
+        }
+
+        let influence: Vec<Traits> = influencers.iter().map(|p| Traits::from_dna(p.dna)).collect();
+        let odds = rarity::harvest_odds(&traits.blend(&influence), parent.care().health);
+        let h = if policy.vtype == vtypes::PLANT {
+            // a seed plant's harvest is a new plant, grown from it alone (helpers only make it rarer)
+            let ptype = self.seedling_type(&traits);
+            self.create_veggie_from(vtypes::PLANT, ptype, &plants[..1], GenePick::Tiered(odds))
+        } else {
+            self.create_veggie_from(vtypes::HARVEST, policy.yields, &plants, GenePick::Tiered(odds))
+        };
+        events::plantary_harvest(&caller, h.vid, parent_id, influencer_ids, h.dna);
+
+        let mut parent = plants.swap_remove(0);
+        parent.harvested_at = now;
+        parent.harvest_season = growth::season(&traits, parent.planted_at, now);
+        parent.season_harvests = harvests + 1;
+        self.veggies.insert(&parent_id, &parent);
+        h
+    }
+
+    // what kind of plant a seed plant's seeds grow into: its DNA picks one of the kinds there's art for
+    fn seedling_type(&self, traits: &Traits) -> PlantType {
+        let types: Vec<PlantType> = (1..P_NAMES.len() as PlantType)
+            .filter(|t| !self.genome.available(vtypes::PLANT, *t).is_empty())
+            .collect();
+        if types.is_empty() {
+            env::panic(b"No artwork available for seedlings.");
+        }
+        types[traits.seed_type as usize % types.len()]
+    }
+
+    // the plant types a random plant can be, with their weights: those with a weight,
+    // that can be minted right now (there's art left for them, or none was ever made)
+    fn random_plant_weights(&self) -> Vec<(PlantType, u32)> {
+        (1..P_NAMES.len() as PlantType)
+            .map(|t| (t, self.prices.random_weight(t)))
+            .filter(|(t, weight)| *weight > 0
+                && (!self.genome.available(vtypes::PLANT, *t).is_empty() || !self.genome.has_art(vtypes::PLANT, *t)))
+            .collect()
+    }
+
+    fn random_plant_type(&self) -> PlantType {
+        let weights = self.random_plant_weights();
+        let total: u64 = weights.iter().map(|(_, weight)| *weight as u64).sum();
+        if total == 0 {
+            env::panic(b"No plants can be minted at random right now.");
+        }
+        let mut rng: ChaCha8Rng = Seeder::from((env::random_seed(), "random plant")).make_rng();
+        let mut roll = rng.gen_range(0, total);
+        for (ptype, weight) in weights {
+            if roll < weight as u64 {
+                return ptype;
+            }
+            roll -= weight as u64;
+        }
+        unreachable!()
+    }
+
+    fn get_plant(&self, vid: TokenId) -> Veggie {
+        let plant = self.get_veggie(vid);
+        if plant.vtype != vtypes::PLANT {
+            env::panic(b"Not a plant.");
+        }
+        plant
+    }
+
+    // water or fertilize one of the caller's plants
+    fn tend(&mut self, vid: TokenId, action: fn(Care) -> Care) -> PlantStatus {
+        let mut plant = self.get_plant(vid);
+        if self.token_bank.get_token_owner(vid) != env::predecessor_account_id() {
+            env::panic(b"You can only look after plants you own.");
+        }
+        plant.set_care(action(plant.care()));
+        self.veggies.insert(&vid, &plant);
+        self.plant_status(&plant)
+    }
+
+    fn plant_status(&self, plant: &Veggie) -> PlantStatus {
+        let care = plant.care();
+        // (plants that can't be harvested get no harvests)
+        let limits = harvest::limits(&H_POLICY[plant.vsubtype as usize], &Traits::from_dna(plant.dna));
+        PlantStatus {
+            vid: plant.vid.into(),
+            stage: plant.stage().unwrap(),
+            hydration: care.hydration,
+            health: care.health,
+            planted_at: plant.planted_at.into(),
+            cared_at: plant.cared_at.into(),
+            harvested_at: plant.harvested_at.into(),
+            harvests_left: limits.per_season.saturating_sub(plant.harvests_this_season()),
+            harvest_ready_at: (plant.harvested_at + limits.cooldown).into(),
+        }
+    }
+
+    fn breed(&mut self, a_id: TokenId, b_id: TokenId) -> Veggie {
+        if a_id == b_id {
+            env::panic(b"A plant can't breed with itself.");
+        }
+        let caller = env::predecessor_account_id();
+        let now = env::block_timestamp();
+        let mut parents = vec![self.get_veggie(a_id), self.get_veggie(b_id)];
+        for p in parents.iter() {
+            if p.vtype != vtypes::PLANT {
+                env::panic(b"Only plants can breed.");
+            }
+            if self.token_bank.get_token_owner(p.vid) != caller {
+                env::panic(b"You can only breed plants you own.");
+            }
+            if now < p.breed_ready_at {
+                panic!("Plant {} can breed again in {} minutes.", p.vid, growth::minutes_until(p.breed_ready_at, now));
+            }
+        }
+        // (or breeding would be a cheap way to get a pricier type of plant)
+        if parents[0].vsubtype != parents[1].vsubtype {
+            env::panic(b"Only plants of the same type can breed.");
         }
-        // for now, the harvest subtype is the same subtype as the parent plant
-        let h = self.create_veggie(vtypes::HARVEST, parent.vsubtype, parent.vid);
-        return h;
+
+        let child = self.create_veggie_from(vtypes::PLANT, parents[0].vsubtype, &parents, GenePick::Any);
+        for p in parents.iter_mut() {
+            p.breed_ready_at = now + BREED_COOLDOWN;
+            self.veggies.insert(&p.vid, p);
+        }
+        events::plantary_breed(&caller, child.vid, &[a_id, b_id], child.dna, child.generation);
+        child
     }
 
     fn get_owner_veggies_page(&self, owner_id: AccountId, vtype: VeggieType, page_size: u16, page: u16) -> Vec<Veggie> {
         self.check_vtype(vtype);
         // get all owner tokens
         let tokens:TokenSet = self.token_bank.get_owner_tokens(&owner_id); // TokenSet == UnorderedSet<TokenId>
-        // convert to all owner plants
-        let mut owner_veggies: Vec<Veggie> = Vec::new();
-        for ot in tokens.iter() {
-            let ov = self.get_veggie(ot);
-            if (vtype == 0) || (vtype == ov.vtype) { owner_veggies.push(ov); }
+        self.veggies_page(tokens.as_vector(), vtype, page_size, page)
+    }
+
+    // From a list of veggie ids, get a page's worth of veggies of the given vtype (0 == all).
+    // Unfiltered pages are read directly; filtered pages only read as far into the list as the page ends.
+    fn veggies_page(&self, vids: &Vector<TokenId>, vtype: VeggieType, page_size: u16, page: u16) -> Vec<Veggie> {
+        // pagesize 0?  try to return all results
+        let (startpoint, limit) = page_bounds(page_size, page);
+        if vtype == 0 {
+            return page_of(vids, startpoint, limit).into_iter().map(|vid| self.get_veggie(vid)).collect();
         }
+        vids.iter()
+            .map(|vid| self.get_veggie(vid))
+            .filter(|v| v.vtype == vtype)
+            .skip(startpoint as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    // From a run of veggie ids (in id order, from a cursor), get the first `limit` of the given vtype (0 == all).
+    fn veggies_after(&self, vids: impl Iterator<Item = TokenId>, vtype: VeggieType, limit: usize) -> Vec<Veggie> {
+        vids.map(|vid| self.get_veggie(vid))
+            .filter(|v| vtype == 0 || v.vtype == vtype)
+            .take(limit)
+            .collect()
+    }
 
-        // calculate page, return it
-        let count = owner_veggies.len();
+    // the JSON version, with the art for the veggie's growth stage
+    fn veggie_json(&self, v: Veggie) -> VeggieJSON {
+        let mut json = VeggieJSON::from(v);
+        if let Some(stage) = json.stage {
+            let art = self.genome.stage_art(json.vtype, json.vsubtype, stage);
+            if !art.is_empty() {
+                let gene_id = art[(u64::from(json.dna) % art.len() as u64) as usize];
+                json.stage_meta_url = Some(self.genome.get(gene_id).meta_url);
+            }
+        }
+        json
+    }
 
-        // pagesize 0?  try to return all results 
-        if page_size == 0 {
-            return owner_veggies;
+    // what harvesting this plant gives; panics if it can't be harvested.
+    fn harvest_policy(&self, plant: &Veggie) -> &'static HarvestPolicy {
+        if plant.vtype != vtypes::PLANT {
+            env::panic(b"non-plant harvest");
+        }
+        let policy = &H_POLICY[plant.vsubtype as usize];
+        if !policy.harvestable {
+            panic!("{}s can't be harvested.", P_NAMES[plant.vsubtype as usize]);
         }
+        policy
+    }
+
+    // what harvesting a plant costs
+    fn harvest_price(&self, plant: &Veggie) -> Balance {
+        self.harvest_policy(plant); // (panics if it can't be harvested)
+        self.prices.harvest(plant.vsubtype)
+    }
 
-        let startpoint: usize = page_size as usize * page as usize;
-        if startpoint > count { return Vec::new(); }
+    // how rare a plant's harvests are likely to be
+    fn harvest_odds(&self, plant: &Veggie) -> Odds {
+        if plant.vtype != vtypes::PLANT {
+            env::panic(b"Only plants have harvests.");
+        }
+        rarity::harvest_odds(&Traits::from_dna(plant.dna), plant.care().health)
+    }
 
-        let mut endpoint : usize =  startpoint + page_size as usize;
-        if endpoint > count { endpoint = count; }
+    // add NEP-177 metadata to the token bank's view of a token
+    // (veggies whose artwork has no media of its own get the art drawn from their DNA)
+    fn with_metadata(&self, mut token: NFTokenJSON) -> NFTokenJSON {
+        token.metadata = self.veggies.get(&token.token_id.into()).map(|v| {
+            let mut metadata = v.metadata.clone();
+            if metadata.media.is_none() {
+                metadata.media = Some(render::data_uri(&self.veggie_svg(&v)));
+            }
+            metadata
+        });
+        token
+    }
 
-        owner_veggies[startpoint .. endpoint].to_vec()
+    fn veggie_svg(&self, v: &Veggie) -> String {
+        render::svg(v.vtype, v.dna, v.stage())
     }
 
     // panic if invalid veggie types are attempted.
@@ -262,13 +814,29 @@ impl PlantaryContract {
         assert_eq!(env::predecessor_account_id(), self.owner_id, "Only contract owner can call this method.");
     }
 
-    // panic unless exactly 'tokens' N are  attached
+    // panic unless a curator (or the owner) is calling
+    fn only_curator(&self) {
+        let predecessor = env::predecessor_account_id();
+        assert!(predecessor == self.owner_id || self.curators.contains(&predecessor), "Only curators can call this method.");
+    }
+
+    // panic unless at least 'tokens' N are attached; anything over that goes back to the caller.
+    // (wallets may attach a little extra, e.g. for storage.)
     fn paid_up(&self, tokens: Balance) {
-        let yocto = tokens * 10u128.pow(24);
+        self.paid_up_yocto(prices::to_yocto(tokens));
+    }
+
+    // ... the same, for a price in yoctoNEAR
+    fn paid_up_yocto(&self, yocto: Balance) {
         let dep = env::attached_deposit();
-        if dep != yocto {
+        if dep < yocto {
             panic!("needed {} yn, received {}", yocto, dep);
         }
+        let refund = dep - yocto;
+        if refund > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(refund);
+        }
+        env::log(format!("Charged {} yn, refunded {} yn.", yocto, refund).as_bytes());
     }
 
     // create a veggie with tokenID and random properties
@@ -277,6 +845,19 @@ impl PlantaryContract {
                     vsubtype: VeggieSubType,
                     parent_vid: TokenId,
                     ) -> Veggie {
+        let parents = if parent_vid == 0 { vec![] } else { vec![self.get_veggie(parent_vid)] };
+        self.create_veggie_from(vtype, vsubtype, &parents, GenePick::Any)
+    }
+
+    // ... with its artwork picked as asked.  A plant with (two) parents is bred from them;
+    // a harvest inherits its traits from its parent and any influencers that follow it.
+    fn create_veggie_from(&mut self,
+                    vtype: VeggieType,
+                    vsubtype: VeggieSubType,
+                    parents: &[Veggie],
+                    pick: GenePick,
+                    ) -> Veggie {
+        let parent_vid = parents.first().map_or(0, |p| p.vid);
 
         // seed RNG
         let mut rng: ChaCha8Rng = Seeder::from(env::random_seed()).make_rng();
@@ -291,19 +872,78 @@ impl PlantaryContract {
             }
         }
 
-        // pick a meta URL at random from the plant pool for the given subtype
-        let meta_url: String;
-        let subtypes;
-        if vtype == vtypes::PLANT {
-            subtypes = &P_POOL[&vsubtype];
+        // pick an artwork at random from the genome's pool for the given type and subtype
+        let default_title = if vtype == vtypes::PLANT {
+            P_NAMES[vsubtype as usize]
         } else {
-            subtypes = &H_POOL[&vsubtype];
-        }
-        meta_url = subtypes[rng.gen_range(0, subtypes.len())].to_string();
+            H_NAMES[vsubtype as usize]
+        };
+        let gene_ids = match pick {
+            GenePick::Exactly(gene_id) => vec![gene_id],
+            GenePick::Any => self.genome.available(vtype, vsubtype),
+            GenePick::Tiered(odds) => {
+                let tier = rarity::draw(&odds, rng.gen_range(0, rarity::ALL_ODDS));
+                self.genome.available_nearest(vtype, vsubtype, tier)
+            }
+        };
+        // veggies of a type nobody has made art for yet have only the art drawn from their DNA
+        let gene = if gene_ids.is_empty() && !self.genome.has_art(vtype, vsubtype) {
+            None
+        } else {
+            if gene_ids.is_empty() {
+                panic!("No artwork available for {}.", default_title);
+            }
+            let gene_id = if gene_ids.len() == 1 {
+                gene_ids[0]
+            } else {
+                gene_ids[rng.gen_range(0, gene_ids.len())]
+            };
+            Some((gene_id, self.genome.record_mint(gene_id)))
+        };
+        let metadata = match &gene {
+            Some((_, gene)) => TokenMetadata::for_gene(gene, default_title),
+            None => TokenMetadata::drawn(default_title),
+        };
 
-        let dna: u64 = rng.gen();
+        // a plant with parents was bred from two, or grown from a seed plant's seed
+        let bred = vtype == vtypes::PLANT && !parents.is_empty();
+        let dna = if bred {
+            let other = match parents.get(1) {
+                Some(p) => p.dna,
+                None => rng.gen(), // wild
+            };
+            dna::cross(parents[0].dna, other, &mut rng)
+        } else {
+            let parent_dna: Vec<u64> = parents.iter().map(|p| p.dna).collect();
+            dna::inherit(rng.gen(), &parent_dna, &mut rng)
+        };
 
-        let v = Veggie::new(vid, parent_vid, vtype, vsubtype, dna, &meta_url);
+        let meta_url = gene.as_ref().map_or_else(String::new, |(_, gene)| gene.meta_url.clone());
+        let mut v = Veggie::new(vid, parent_vid, vtype, vsubtype, dna, &meta_url, metadata);
+        v.gene_id = NO_GENE;
+        if let Some((gene_id, gene)) = gene {
+            v.royalty = gene.royalty();
+            v.gene_id = gene_id;
+            v.rarity = gene.rarity;
+        }
+        v.planted_at = env::block_timestamp();
+        v.cared_at = v.planted_at;
+        if vtype == vtypes::PLANT {
+            v.name = Some(names::binomial(vsubtype, dna));
+            v.metadata.title = v.name.clone();
+        } else {
+            v.text = text::grammar(vsubtype).map(|g| g.generate(dna));
+            if vsubtype == ptypes::LUCKY {
+                v.lucky = Some(LuckyDraw::new(&Traits::from_dna(parents[0].dna), dna, env::random_seed()));
+            }
+        }
+        if bred {
+            v.co_parent = parents.get(1).map_or(0, |p| p.vid);
+            v.generation = parents.iter().map(|p| p.generation).max().unwrap() + 1;
+        } else {
+            v.influencers = parents.iter().skip(1).map(|p| p.vid).collect();
+            v.generation = parents.first().map_or(0, |p| p.generation);
+        }
         assert_eq!(vid, v.vid, "vid mismatch!");
 
         // record in the static list of veggies
@@ -326,6 +966,12 @@ pub struct PlantaryContract {
     pub owner_id: AccountId,
     // metadata storage
     pub veggies: UnorderedMap<TokenId, Veggie>,
+    // the artworks veggies are made from
+    pub genome: Genome,
+    // who may publish artworks to the genome (besides the owner)
+    pub curators: UnorderedSet<AccountId>,
+    // what minting, harvesting, breeding and fertilizing cost
+    pub prices: Prices,
 }
 
 impl Default for PlantaryContract {
@@ -341,17 +987,119 @@ impl PlantaryContract {
     pub fn new(owner_id: AccountId) -> Self {
         assert!(env::is_valid_account_id(owner_id.as_bytes()), "Owner's account ID is invalid.");
         assert!(!env::state_exists(), "Already initialized");
+
         Self {
             token_bank: TokenBank::new(),
             owner_id,
             veggies: UnorderedMap::new(b"veggies".to_vec()),
+            genome: Genome::launched(),
+            curators: UnorderedSet::new(b"curators".to_vec()),
+            prices: Prices::new(),
         }
     }
 
+    // Run once, after deploying this version over the first version's state (see migration.rs).
+    #[init]
+    pub fn migrate() -> Self {
+        let old: OldPlantaryContract = env::state_read().expect("Contract is not initialized.");
+        let predecessor = env::predecessor_account_id();
+        assert!(predecessor == old.owner_id || predecessor == env::current_account_id(), "Only contract owner can call this method.");
+        old.upgrade()
+    }
+
     pub fn get_owner_tokens(&self, owner_id: &AccountId) -> Vec<TokenJSON> {
         self.token_bank.get_owner_tokens(&owner_id).iter().map(|t| TokenJSON::from(t)).collect()
     }
 
+    pub fn get_tokens_page(&self, page_size: u16, page: u16) -> Vec<TokenJSON> {
+        self.token_bank.get_tokens_page(page_size, page).into_iter().map(TokenJSON::from).collect()
+    }
+
+    // up to `limit` (0 == all) token ids after `after`, in id order; pass the last one back to get the next page.
+    pub fn get_tokens_after(&self, after: Option<TokenJSON>, limit: u16) -> Vec<TokenJSON> {
+        self.token_bank.tokens_after(after.map(TokenId::from)).take(limit_or_all(limit)).map(TokenJSON::from).collect()
+    }
+
+    pub fn get_veggies_page(&self, vtype: VeggieType, page_size: u16, page: u16) -> Vec<VeggieJSON> {
+        self.check_vtype(vtype);
+        self.veggies_page(self.veggies.keys_as_vector(), vtype, page_size, page).into_iter().map(|v| self.veggie_json(v)).collect()
+    }
+
+    // up to `limit` (0 == all) veggies of the given vtype after veggie `after`, in id order; see get_owner_veggies_after.
+    pub fn get_veggies_after(&self, vtype: VeggieType, after: Option<TokenJSON>, limit: u16) -> Vec<VeggieJSON> {
+        self.check_vtype(vtype);
+        let vids = self.token_bank.tokens_after(after.map(TokenId::from));
+        self.veggies_after(vids, vtype, limit_or_all(limit)).into_iter().map(|v| self.veggie_json(v)).collect()
+    }
+
+    // what everything costs, in NEAR
+    pub fn get_price_list(&self) -> PriceList {
+        self.prices.list()
+    }
+
+    // prices can be changed by curators (or the owner).  Prices are in whole NEAR.
+    pub fn set_plant_price(&mut self, ptype: PlantType, price: json_types::U128) {
+        self.only_curator();
+        self.prices.set_plant(ptype, price.into());
+    }
+
+    pub fn set_harvest_price(&mut self, ptype: PlantType, price: json_types::U128) {
+        self.only_curator();
+        self.prices.set_harvest(ptype, price.into());
+    }
+
+    pub fn set_random_plant_price(&mut self, price: json_types::U128) {
+        self.only_curator();
+        self.prices.random = check_price(price.into());
+    }
+
+    // how likely a random plant is to be each type, relative to the others; 0 takes a type out of the draw
+    pub fn set_random_plant_weight(&mut self, ptype: PlantType, weight: u32) {
+        self.only_curator();
+        self.prices.set_random_weight(ptype, weight);
+    }
+
+    // the odds of each type a random plant can be right now, in basis points
+    pub fn get_random_plant_odds(&self) -> Vec<TypeOdds> {
+        let weights = self.random_plant_weights();
+        let total: u64 = weights.iter().map(|(_, weight)| *weight as u64).sum();
+        weights.into_iter()
+            .map(|(ptype, weight)| TypeOdds { ptype, name: P_NAMES[ptype as usize].to_string(), bps: (weight as u64 * ALL_ODDS as u64 / total) as u32 })
+            .collect()
+    }
+
+    pub fn set_breed_price(&mut self, price: json_types::U128) {
+        self.only_curator();
+        self.prices.breed = check_price(price.into());
+    }
+
+    pub fn set_fertilizer_price(&mut self, price: json_types::U128) {
+        self.only_curator();
+        self.prices.fertilizer = check_price(price.into());
+    }
+
+    // curators approve and publish artwork; the owner picks the curators.
+    pub fn grant_curator(&mut self, account_id: AccountId) {
+        self.only_owner();
+        assert!(env::is_valid_account_id(account_id.as_bytes()), "Curator's account ID is invalid.");
+        self.curators.insert(&account_id);
+    }
+
+    pub fn revoke_curator(&mut self, account_id: AccountId) {
+        self.only_owner();
+        if !self.curators.remove(&account_id) {
+            env::panic(b"Not a curator.")
+        }
+    }
+
+    pub fn is_curator(&self, account_id: AccountId) -> bool {
+        self.curators.contains(&account_id)
+    }
+
+    pub fn get_curators(&self) -> Vec<AccountId> {
+        self.curators.to_vec()
+    }
+
     // debug 
     pub fn get_veggie_keys(&self) -> Vec<TokenJSON> {
         self.veggies.keys().map(|i| TokenJSON::from(i)).collect()
@@ -391,49 +1139,201 @@ impl NEP4 for PlantaryContract {
     }
 }
 
+// Expose NEP-171 interface of TokenBank
+#[near_bindgen]
+impl NEP171 for PlantaryContract {
+    #[payable]
+    fn nft_transfer(&mut self, receiver_id: AccountId, token_id: TokenJSON, approval_id: Option<u64>, memo: Option<String>) {
+        self.token_bank.nft_transfer(receiver_id, token_id, approval_id, memo)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use near_sdk::MockedBlockchain;
-    use near_sdk::{testing_env, VMContext, Balance};
-    use constants::{vtypes, ptypes};
+    #[payable]
+    fn nft_transfer_call(&mut self, receiver_id: AccountId, token_id: TokenJSON, approval_id: Option<u64>, memo: Option<String>, msg: String) -> PromiseOrValue<bool> {
+        self.token_bank.nft_transfer_call(receiver_id, token_id, approval_id, memo, msg)
+    }
 
-    fn to_ynear(near: Balance) -> Balance {
-        near * 10u128.pow(24)
+    fn nft_token(&self, token_id: TokenJSON) -> Option<NFTokenJSON> {
+        self.token_bank.nft_token(token_id).map(|token| self.with_metadata(token))
     }
+}
 
-    fn joe() -> AccountId {
-        "joe.testnet".to_string()
+// Expose NEP-181 interface of TokenBank
+#[near_bindgen]
+impl NEP181 for PlantaryContract {
+    fn nft_total_supply(&self) -> json_types::U128 {
+        self.token_bank.nft_total_supply()
     }
-    fn robert() -> AccountId {
-        "robert.testnet".to_string()
+
+    fn nft_tokens(&self, from_index: Option<json_types::U128>, limit: Option<u64>) -> Vec<NFTokenJSON> {
+        self.token_bank.nft_tokens(from_index, limit).into_iter().map(|t| self.with_metadata(t)).collect()
     }
-    fn mike() -> AccountId {
-        "mike.testnet".to_string()
+
+    fn nft_supply_for_owner(&self, account_id: AccountId) -> json_types::U128 {
+        self.token_bank.nft_supply_for_owner(account_id)
     }
 
-    // part of writing unit tests is setting up a mock context
-    // this is a useful list to peek at when wondering what's available in env::*
-    fn get_context(predecessor_account_id: String, storage_usage: u64) -> VMContext {
-        VMContext {
-            current_account_id: "alice.testnet".to_string(),
-            signer_account_id: "jane.testnet".to_string(),
-            signer_account_pk: vec![0, 1, 2],
-            predecessor_account_id,
-            input: vec![],
-            block_index: 0,
-            block_timestamp: 0,
-            account_balance: 10u128.pow(28),
-            account_locked_balance: 0,
-            storage_usage,
-            attached_deposit: 10u128.pow(27),
-            prepaid_gas: 10u64.pow(18),
-            random_seed: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
-            is_view: false,
-            output_data_receivers: vec![],
-            epoch_height: 19,
-        }
+    fn nft_tokens_for_owner(&self, account_id: AccountId, from_index: Option<json_types::U128>, limit: Option<u64>) -> Vec<NFTokenJSON> {
+        self.token_bank.nft_tokens_for_owner(account_id, from_index, limit).into_iter().map(|t| self.with_metadata(t)).collect()
+    }
+}
+
+// NEP-177 metadata.  (token metadata comes along with nft_token and get_veggie_json.)
+#[near_bindgen]
+impl NEP177 for PlantaryContract {
+    fn nft_metadata(&self) -> NFTContractMetadata {
+        NFTContractMetadata::plantary()
+    }
+}
+
+#[near_bindgen]
+impl NEP171Resolver for PlantaryContract {
+    fn nft_resolve_transfer(&mut self, owner_id: AccountId, receiver_id: AccountId, token_id: TokenJSON, approved_account_ids: Option<HashMap<AccountId, u64>>) -> bool {
+        self.token_bank.nft_resolve_transfer(owner_id, receiver_id, token_id, approved_account_ids)
+    }
+}
+
+// Expose NEP-178 interface of TokenBank
+#[near_bindgen]
+impl NEP178 for PlantaryContract {
+    #[payable]
+    fn nft_approve(&mut self, token_id: TokenJSON, account_id: AccountId, msg: Option<String>) {
+        self.token_bank.nft_approve(token_id, account_id, msg)
+    }
+
+    #[payable]
+    fn nft_revoke(&mut self, token_id: TokenJSON, account_id: AccountId) {
+        self.token_bank.nft_revoke(token_id, account_id)
+    }
+
+    #[payable]
+    fn nft_revoke_all(&mut self, token_id: TokenJSON) {
+        self.token_bank.nft_revoke_all(token_id)
+    }
+
+    fn nft_is_approved(&self, token_id: TokenJSON, approved_account_id: AccountId, approval_id: Option<u64>) -> bool {
+        self.token_bank.nft_is_approved(token_id, approved_account_id, approval_id)
+    }
+}
+
+// The genome: what new veggies can look like.  Artists submit, curators publish.
+#[near_bindgen]
+impl Genes for PlantaryContract {
+    fn add_gene(&mut self, gene: GeneSpec) -> json_types::U64 {
+        self.only_curator();
+        self.genome.add(Gene::from_spec(gene)).into()
+    }
+
+    #[payable]
+    fn submit_gene(&mut self, gene: GeneSpec) -> json_types::U64 {
+        // the artist pays for what their submission stores, not the contract
+        let storage_before = env::storage_usage();
+        let gene_id = self.genome.submit(gene, env::predecessor_account_id());
+        let stored = env::storage_usage().saturating_sub(storage_before);
+        self.paid_up_yocto(stored as Balance * STORAGE_PRICE_PER_BYTE);
+        gene_id.into()
+    }
+
+    fn approve_gene(&mut self, gene_id: json_types::U64, price: json_types::U128, max_supply: Option<json_types::U64>) {
+        self.only_curator();
+        self.genome.approve(gene_id.into(), check_price(price.into()), max_supply.map(u64::from))
+    }
+
+    fn reject_gene(&mut self, gene_id: json_types::U64) {
+        self.only_curator();
+        self.genome.reject(gene_id.into())
+    }
+
+    fn update_gene(&mut self, gene_id: json_types::U64, gene: GeneSpec) {
+        self.only_curator();
+        self.genome.update(gene_id.into(), gene)
+    }
+
+    fn retire_gene(&mut self, gene_id: json_types::U64) {
+        self.only_curator();
+        self.genome.retire(gene_id.into())
+    }
+
+    fn get_gene(&self, gene_id: json_types::U64) -> GeneJSON {
+        let gene_id = GeneId::from(gene_id);
+        GeneJSON::new(gene_id, self.genome.get(gene_id))
+    }
+
+    fn get_genes(&self, vtype: VeggieType, vsubtype: VeggieSubType, page_size: u16, page: u16) -> Vec<GeneJSON> {
+        self.genome.page(vtype, vsubtype, page_size, page)
+    }
+
+    fn get_pending_genes(&self, page_size: u16, page: u16) -> Vec<GeneJSON> {
+        self.genome.pending_page(page_size, page)
+    }
+}
+
+// NEP-199 royalties, from each veggie's artwork
+#[near_bindgen]
+impl NEP199 for PlantaryContract {
+    fn nft_payout(&self, token_id: TokenJSON, balance: json_types::U128, max_len_payout: u32) -> Payout {
+        let vid = TokenId::from(token_id);
+        let owner_id = self.token_bank.get_token_owner(vid);
+        Payout::for_sale(&self.get_veggie(vid).royalty, &owner_id, balance.into(), max_len_payout)
+    }
+
+    #[payable]
+    fn nft_transfer_payout(&mut self, receiver_id: AccountId, token_id: TokenJSON, approval_id: Option<u64>, memo: Option<String>, balance: json_types::U128, max_len_payout: u32) -> Payout {
+        // the payout goes to whoever owned it before the sale
+        let payout = self.nft_payout(token_id, balance, max_len_payout);
+        self.token_bank.nft_transfer(receiver_id, token_id, approval_id, memo);
+        payout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::MockedBlockchain;
+    use near_sdk::{testing_env, VMContext, Balance};
+    use constants::{vtypes, ptypes, P_PRICES, BREED_PRICE, FERTILIZER_PRICE, RANDOM_PLANT_PRICE};
+    use genome::GeneStatus;
+
+    fn to_ynear(near: Balance) -> Balance {
+        near * 10u128.pow(24)
+    }
+
+    fn joe() -> AccountId {
+        "joe.testnet".to_string()
+    }
+    fn robert() -> AccountId {
+        "robert.testnet".to_string()
+    }
+    fn mike() -> AccountId {
+        "mike.testnet".to_string()
+    }
+
+    // part of writing unit tests is setting up a mock context
+    // this is a useful list to peek at when wondering what's available in env::*
+    fn get_context(predecessor_account_id: String, storage_usage: u64) -> VMContext {
+        VMContext {
+            current_account_id: "alice.testnet".to_string(),
+            signer_account_id: "jane.testnet".to_string(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id,
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 10u128.pow(28),
+            account_locked_balance: 0,
+            storage_usage,
+            attached_deposit: 10u128.pow(27),
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 19,
+        }
+    }
+
+    // a day and a bit after get_context's, when every plant minted then is mature
+    const GROWN: u64 = 30 * growth::HOUR;
+    fn get_grown_context(predecessor_account_id: String, storage_usage: u64) -> VMContext {
+        VMContext { block_timestamp: GROWN, ..get_context(predecessor_account_id, storage_usage) }
     }
 
     #[test]
@@ -473,6 +1373,8 @@ mod tests {
 
             // create
         let p = contract.mint_plant(ptypes::PORTRAIT);
+        let c = get_grown_context(robert(), env::storage_usage());
+        testing_env!(c);
         let h = contract.harvest_plant(p.vid);
             // inspect
         assert_eq!(p.vid, h.parent, "parentage suspect");
@@ -504,6 +1406,22 @@ mod tests {
         let _nov = contract.get_veggie_json(vid_json); // should panic
     }
 
+    #[test]
+    fn delete_veggie_burns_token(){
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let c = get_context(joe(), env::storage_usage());
+        testing_env!(c);
+        let p = contract.mint_plant(ptypes::MONEY);
+
+        // the contract owner can delete joe's veggie; it's burned, not orphaned
+        let c = get_context(robert(), env::storage_usage());
+        testing_env!(c);
+        contract.delete_veggie(p.vid);
+        assert_eq!(0, contract.count_owner_veggies(joe(), 0), "deleted veggie still counted");
+        assert!(contract.nft_token(p.vid.into()).is_none(), "deleted veggie still has a token");
+    }
+
     #[test]
     fn count_owner_veggies(){
         let c = get_context(robert(), 0);
@@ -518,6 +1436,8 @@ mod tests {
         let _p3 = contract.mint_plant(ptypes::PORTRAIT);
 
         // harvest some fruit
+        let c = get_grown_context(robert(), env::storage_usage());
+        testing_env!(c);
         let _h1 = contract.harvest_plant(_p2.vid);
         let _h2 = contract.harvest_plant(_p3.vid);
 
@@ -547,18 +1467,25 @@ mod tests {
         let c = get_context(robert(), 0);
         testing_env!(c);
         let mut contract = PlantaryContract::new(robert());
+        // (each of these would be its own call, with its own gas)
+        let c = get_context(robert(), env::storage_usage());
+        testing_env!(c);
 
         // mint 23  plants
-        for _n in 0..22 {
+        for _n in 0..10 {
             contract.mint_plant(ptypes::MONEY);
         }
-        let _p23 = contract.mint_plant(ptypes::ORACLE);
+        let oracles: Vec<Veggie> = (0..13).map(|_| contract.mint_plant(ptypes::ORACLE)).collect();
 
         // mint 13 harvests
-        for _o in 0..13 {
-            contract.harvest_plant(_p23.vid);
+        let c = get_grown_context(robert(), env::storage_usage());
+        testing_env!(c);
+        for o in oracles.iter() {
+            contract.harvest_plant(o.vid);
         }
 
+        let c = get_context(robert(), env::storage_usage());
+        testing_env!(c);
         // test plants:
         // get three pages of size 7
         // check that they are all full
@@ -588,22 +1515,62 @@ mod tests {
 
     }
 
+    #[test]
+    fn get_veggies_after(){
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let c = get_context(robert(), env::storage_usage());
+        testing_env!(c);
+        for _n in 0..5 {
+            contract.mint_plant(ptypes::ORACLE);
+        }
+        let vids: Vec<TokenId> = contract.get_tokens_after(None, 0).into_iter().map(TokenId::from).collect();
+        assert_eq!(5, vids.len(), "a limit of 0 gets every token");
+        assert_eq!(vids[3..], contract.get_tokens_after(Some(vids[2].into()), 2).into_iter().map(TokenId::from).collect::<Vec<TokenId>>()[..]);
+
+        // walk robert's plants two at a time, while one goes to joe and another is deleted
+        let first = contract.get_owner_veggies_after(robert(), vtypes::PLANT, None, 2);
+        assert_eq!(vids[..2], first.iter().map(|v| TokenId::from(v.vid)).collect::<Vec<TokenId>>()[..]);
+        contract.transfer(joe(), vids[0]);
+        contract.delete_veggie(vids[1]);
+        let new = contract.mint_plant(ptypes::MONEY).vid;
+        let rest = contract.get_owner_veggies_after(robert(), vtypes::PLANT, Some(first[1].vid), 0);
+        let mut expected = vids[2..].to_vec();
+        if new > vids[1] {
+            // (a new plant lands wherever its id puts it)
+            expected.push(new);
+            expected.sort_unstable();
+        }
+        assert_eq!(expected, rest.iter().map(|v| TokenId::from(v.vid)).collect::<Vec<TokenId>>(), "the rest of the plants should follow on");
+
+        let all = contract.get_veggies_after(vtypes::PLANT, None, 0);
+        assert_eq!(5, all.len(), "joe's plant should be listed too");
+        assert!(all.windows(2).all(|w| TokenId::from(w[0].vid) < TokenId::from(w[1].vid)), "veggies should come in id order");
+    }
+
     #[test]
     fn get_owner_veggies_page_2(){
         testing_env!(get_context(robert(), 0));
         let mut contract = PlantaryContract::new(robert());
+        // (each of these would be its own call, with its own gas)
+        let c = get_context(robert(), env::storage_usage());
+        testing_env!(c);
 
         // mint 23  plants
-        for _n in 0..22 {
+        for _n in 0..10 {
             contract.mint_plant(ptypes::MONEY);
         }
-        let _p23 = contract.mint_plant(ptypes::ORACLE);
+        let oracles: Vec<Veggie> = (0..13).map(|_| contract.mint_plant(ptypes::ORACLE)).collect();
 
         // mint 13 harvests
-        for _o in 0..13 {
-            contract.harvest_plant(_p23.vid);
+        let c = get_grown_context(robert(), env::storage_usage());
+        testing_env!(c);
+        for o in oracles.iter() {
+            contract.harvest_plant(o.vid);
         }
 
+        let c = get_context(robert(), env::storage_usage());
+        testing_env!(c);
         // test harvests:
         for p in 0..2 {
             let tokens = contract.get_owner_veggies_page(robert(), vtypes::HARVEST, 4,p);
@@ -638,6 +1605,1039 @@ mod tests {
         let _foo = contract.get_owner_veggies_page(robert(), 23, 1, 1); // panic!
     }
 
+    #[test]
+    fn nft_transfer_veggie() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let p = contract.mint_plant(ptypes::ORACLE);
+
+        let mut c = get_context(robert(), env::storage_usage());
+        c.attached_deposit = 1;
+        testing_env!(c);
+        contract.nft_transfer(joe(), p.vid.into(), None, None);
+
+        let token = contract.nft_token(p.vid.into()).unwrap();
+        assert_eq!(joe(), token.owner_id, "plant was not transferred");
+        assert_eq!(joe(), contract.get_token_owner(p.vid), "NEP4 disagrees with NEP-171 about the owner");
+    }
+
+    #[test]
+    fn nft_approve_veggie() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let p = contract.mint_plant(ptypes::ORACLE);
+
+        // Robert lists his plant with Mike ...
+        let mut c = get_context(robert(), env::storage_usage());
+        c.attached_deposit = to_ynear(1);
+        testing_env!(c);
+        contract.nft_approve(p.vid.into(), mike(), None);
+        let token = contract.nft_token(p.vid.into()).unwrap();
+        assert_eq!(Some(&0), token.approved_account_ids.get(&mike()), "approval missing from nft_token");
+
+        // ... who sells it to Joe.
+        c = get_context(mike(), env::storage_usage());
+        c.attached_deposit = 1;
+        testing_env!(c);
+        contract.nft_transfer(joe(), p.vid.into(), Some(0), None);
+        assert_eq!(joe(), contract.get_token_owner(p.vid), "plant was not transferred");
+        assert!(!contract.nft_is_approved(p.vid.into(), mike(), None), "approval outlived the sale");
+    }
+
+    #[test]
+    fn veggie_metadata() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+
+        let meta = contract.nft_metadata();
+        assert_eq!("Plantary", meta.name, "bad contract name");
+        assert_eq!("nft-1.0.0", meta.spec, "bad metadata spec");
+
+        let p = contract.mint_plant(ptypes::MONEY);
+        assert_eq!(Some(p.meta_url.clone()), p.metadata.reference, "metadata should reference the artwork JSON");
+        assert!(p.metadata.title.is_some(), "plant has no title");
+        assert!(p.metadata.issued_at.is_some(), "plant has no issue date");
+
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let c = get_grown_context(robert(), env::storage_usage());
+        testing_env!(c);
+        let h = contract.harvest_plant(o.vid);
+        let v = contract.get_veggie_json(h.vid.into());
+        assert_eq!(h.metadata, v.metadata, "get_veggie_json lost the metadata");
+
+        // the artwork has no media of its own, so the token gets the plant as drawn from its DNA
+        let token = contract.nft_token(p.vid.into()).unwrap();
+        let media = Some(render::data_uri(&contract.get_veggie_svg(p.vid.into())));
+        assert_eq!(Some(TokenMetadata { media, ..p.metadata }), token.metadata, "nft_token lost the metadata");
+    }
+
+    #[test]
+    fn plant_names() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        assert_eq!(Some(names::binomial(ptypes::ORACLE, o.dna)), o.name, "name should come from the DNA");
+        let json = contract.get_veggie_json(o.vid.into());
+        assert_eq!(o.name, json.name);
+        assert_eq!(o.name, json.metadata.title, "name should be the title");
+        assert_eq!(None, json.nickname);
+
+        let json = contract.set_nickname(o.vid.into(), "Cassandra".to_string());
+        assert_eq!(Some("Cassandra".to_string()), json.nickname);
+        assert_eq!(o.name, json.name, "nickname shouldn't change the name");
+        assert_eq!(None, contract.set_nickname(o.vid.into(), "".to_string()).nickname, "nickname not taken away");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Nicknames can be at most 32 characters."#)]
+    fn nickname_too_long() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        contract.set_nickname(o.vid.into(), "x".repeat(33));
+    }
+
+    #[test]
+    #[should_panic(expected = r#"You can only nickname veggies you own."#)]
+    fn nickname_not_owner() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let c = get_context(mike(), env::storage_usage());
+        testing_env!(c);
+        contract.set_nickname(o.vid.into(), "Mine now".to_string());
+    }
+
+    #[test]
+    fn drawn_veggies() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        // nobody has made art for insult plants yet
+        let p = contract.mint_plant(ptypes::INSULT);
+        assert_eq!(NO_GENE, p.gene_id);
+        assert_eq!("", p.meta_url);
+        assert_eq!(p.name, p.metadata.title);
+
+        let svg = contract.get_veggie_svg(p.vid.into());
+        assert_eq!(render::svg(vtypes::PLANT, p.dna, Some(Stage::Seed)), svg);
+        let c = get_grown_context(robert(), env::storage_usage());
+        testing_env!(c);
+        assert_ne!(svg, contract.get_veggie_svg(p.vid.into()), "art should grow with the plant");
+        let media = contract.nft_token(p.vid.into()).unwrap().metadata.unwrap().media.unwrap();
+        assert!(media.starts_with("data:image/svg+xml,"), "token media should be the drawing");
+        assert_eq!(NO_GENE, contract.harvest_plant(p.vid).gene_id, "insult harvests have no art either");
+
+        // stage art is never minted, so it doesn't count
+        let mut spec = gene_spec(vtypes::PLANT, ptypes::LUCKY);
+        spec.stage = Some(Stage::Flowering);
+        contract.add_gene(spec);
+        assert_eq!(NO_GENE, contract.mint_plant(ptypes::LUCKY).gene_id, "lucky plants only have stage art");
+    }
+
+    #[test]
+    fn veggie_royalty() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+
+        // the artworks we launched with don't pay anyone
+        let p = contract.mint_plant(ptypes::MONEY);
+        assert!(p.royalty.is_empty(), "launch art has no artist accounts");
+
+        // new art pays its artist
+        let mut spec = gene_spec(vtypes::PLANT, ptypes::MONEY);
+        spec.artist_id = Some(mike());
+        let gene_id = only_gene(&mut contract, spec);
+        let p = contract.mint_plant(ptypes::MONEY);
+        assert_eq!(gene_id, p.gene_id, "veggie not made from the only gene");
+        assert_eq!(Some(&constants::ARTIST_ROYALTY_BPS), p.royalty.get(&mike()), "artist should get royalties");
+        assert_eq!(1, p.royalty.len(), "only the artist gets royalties");
+        assert_eq!(p.royalty, contract.get_veggie_json(p.vid.into()).royalty, "get_veggie_json lost the royalty");
+    }
+
+    #[test]
+    fn veggie_traits() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let p = contract.mint_plant(ptypes::ORACLE);
+
+        let traits = contract.get_veggie_traits(p.vid.into());
+        assert_eq!(dna::Traits::from_dna(p.dna), traits, "traits not from the veggie's DNA");
+        assert_eq!(traits, contract.get_veggie_json(p.vid.into()).traits, "get_veggie_json has the wrong traits");
+    }
+
+    #[test]
+    fn harvest_odds() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let p = contract.mint_plant(ptypes::PORTRAIT);
+
+        let odds = contract.get_harvest_odds(p.vid.into());
+        assert_eq!(5, odds.len(), "should have odds for every tier");
+        assert_eq!(Rarity::Mythic, odds[4].rarity);
+        assert_eq!(rarity::ALL_ODDS, odds.iter().map(|o| o.bps).sum::<u32>(), "odds don't add up");
+        assert_eq!(rarity::harvest_odds(&Traits::from_dna(p.dna), care::FULL)[4], odds[4].bps, "odds not from the plant's DNA");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Only plants have harvests."#)]
+    fn harvest_odds_not_plant() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let p = contract.mint_plant(ptypes::PORTRAIT);
+        let c = get_grown_context(robert(), env::storage_usage());
+        testing_env!(c);
+        let h = contract.harvest_plant(p.vid);
+        contract.get_harvest_odds(h.vid.into());
+    }
+
+    #[test]
+    fn harvest_rarity() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let launch = contract.get_genes(vtypes::HARVEST, ptypes::ORACLE, 0, 0);
+        assert_eq!(Rarity::Common, launch[0].rarity);
+        assert_eq!(Rarity::Mythic, launch[10].rarity, "the voucher should be mythic");
+
+        // whatever tier is drawn, the only art left is mythic, so that's what we get.
+        let mut spec = gene_spec(vtypes::HARVEST, ptypes::ORACLE);
+        spec.rarity = Some(Rarity::Mythic);
+        let gene_id = only_gene(&mut contract, spec);
+        assert_eq!(vec![gene_id], contract.genome.available_in(vtypes::HARVEST, ptypes::ORACLE, Rarity::Mythic));
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let c = get_grown_context(robert(), env::storage_usage());
+        testing_env!(c);
+        let h = contract.harvest_plant(o.vid);
+        assert_eq!(gene_id, h.gene_id, "harvest not made from the only gene");
+        assert_eq!(Rarity::Mythic, h.rarity, "harvest should be as rare as its gene");
+        assert_eq!(Rarity::Mythic, contract.get_veggie_json(h.vid.into()).rarity);
+    }
+
+    #[test]
+    fn harvest_with_plants() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let p1 = contract.mint_plant(ptypes::PORTRAIT);
+        let p2 = contract.mint_plant(ptypes::MONEY);
+
+        let mut c = get_grown_context(robert(), env::storage_usage());
+        c.attached_deposit = to_ynear(H_POLICY[ptypes::ORACLE as usize].price);
+        testing_env!(c);
+        let h = contract.harvest_with_plants(o.vid.into(), vec![p1.vid.into(), p2.vid.into()]);
+        assert_eq!(vtypes::HARVEST, h.vtype);
+        assert_eq!(ptypes::ORACLE, h.vsubtype, "harvest should be of the primary plant's type");
+        assert_eq!(TokenJSON::from(o.vid), h.parent);
+        assert_eq!(vec![TokenJSON::from(p1.vid), TokenJSON::from(p2.vid)], h.influencers, "influencers not recorded");
+
+        // every trait came from one of the three
+        let parents: Vec<Traits> = [o.dna, p1.dna, p2.dna].iter().map(|dna| Traits::from_dna(*dna)).collect();
+        assert!(parents.iter().any(|t| t.vigor == h.traits.vigor), "vigor not inherited");
+        assert!(parents.iter().any(|t| t.color == h.traits.color), "color not inherited");
+    }
+
+    #[test]
+    fn harvest_text() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        contract.add_gene(gene_spec(vtypes::PLANT, ptypes::COMPLIMENT));
+        contract.add_gene(gene_spec(vtypes::HARVEST, ptypes::COMPLIMENT));
+        let c = contract.mint_plant(ptypes::COMPLIMENT);
+        let p = contract.mint_plant(ptypes::PORTRAIT);
+
+        let ctx = get_grown_context(robert(), env::storage_usage());
+        testing_env!(ctx);
+        let h = contract.harvest_plant(c.vid);
+        let json = contract.get_veggie_json(h.vid.into());
+        assert_eq!(Some(text::grammar(ptypes::COMPLIMENT).unwrap().generate(h.dna)), json.text, "compliment should be made from its DNA");
+        assert_eq!(None, contract.harvest_plant(p.vid).text, "portraits have no text");
+    }
+
+    #[test]
+    fn harvest_lucky_numbers() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        contract.add_gene(gene_spec(vtypes::PLANT, ptypes::LUCKY));
+        contract.add_gene(gene_spec(vtypes::HARVEST, ptypes::LUCKY));
+        let mut p = contract.mint_plant(ptypes::LUCKY);
+        p.dna = 0xffff; // high yield and vigor: 6 numbers, up to 91
+        contract.veggies.insert(&p.vid, &p);
+
+        let c = get_grown_context(robert(), env::storage_usage());
+        testing_env!(c);
+        let mut h = contract.harvest_plant(p.vid);
+        let draw = h.lucky.clone().unwrap();
+        assert_eq!((6, 91, 6), (draw.count, draw.max, draw.numbers.len()));
+        assert_eq!(env::random_seed(), draw.seed.0, "should keep the block's seed");
+        assert!(contract.verify_lucky_numbers(h.vid.into()));
+        assert_eq!(draw.numbers, contract.draw_lucky_numbers(h.dna.into(), draw.seed, 6, 91));
+
+        // numbers that weren't drawn don't check out
+        h.lucky.as_mut().unwrap().numbers[0] += 100;
+        contract.veggies.insert(&h.vid, &h);
+        assert!(!contract.verify_lucky_numbers(h.vid.into()), "tampered numbers verified");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Lucky numbers can go up to 4294967294 at most."#)]
+    fn draw_lucky_numbers_too_high() {
+        testing_env!(get_context(robert(), 0));
+        let contract = PlantaryContract::new(robert());
+        contract.draw_lucky_numbers(42.into(), vec![1, 2, 3].into(), 6, u32::MAX);
+    }
+
+    #[test]
+    fn harvest_seeds() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        contract.add_gene(gene_spec(vtypes::PLANT, ptypes::SEED));
+        let mut s = contract.mint_plant(ptypes::SEED);
+        // oracle, portrait, money and seed plants have art; seed type 0 grows oracles
+        s.dna = 0;
+        contract.veggies.insert(&s.vid, &s);
+
+        let mut c = get_grown_context(robert(), env::storage_usage());
+        c.attached_deposit = to_ynear(H_POLICY[ptypes::SEED as usize].price);
+        testing_env!(c);
+        let o = contract.harvest_plant_json(s.vid.into());
+        assert_eq!((vtypes::PLANT, ptypes::ORACLE), (o.vtype, o.vsubtype), "seed should grow a plant of the type in its DNA");
+        assert_eq!(TokenJSON::from(s.vid), o.parent);
+        assert_eq!(1, o.generation);
+        assert_eq!(Some(Stage::Seed), o.stage, "new plant should start out as a seed");
+
+        // ... and it can be harvested in its turn
+        let mut c = get_context(robert(), env::storage_usage());
+        c.block_timestamp = 2 * GROWN;
+        testing_env!(c);
+        let h = contract.harvest_plant(o.vid.into());
+        assert_eq!(vec![o.vid, TokenJSON::from(s.vid)], contract.get_lineage(h.vid.into()));
+        assert!(contract.get_lineage(s.vid.into()).is_empty(), "minted plants have no lineage");
+    }
+
+    #[test]
+    fn seedling_type() {
+        testing_env!(get_context(robert(), 0));
+        let contract = PlantaryContract::new(robert());
+        let seed_type = |t: u64| contract.seedling_type(&Traits::from_dna(t << 34));
+        // only oracle, portrait and money plants have art
+        assert_eq!(ptypes::ORACLE, seed_type(0));
+        assert_eq!(ptypes::MONEY, seed_type(2));
+        assert_eq!(ptypes::ORACLE, seed_type(3));
+    }
+
+    #[test]
+    #[should_panic(expected = r#"You can only harvest with plants you own."#)]
+    fn harvest_with_plants_not_owner() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let c = get_context(mike(), env::storage_usage());
+        testing_env!(c);
+        let p = contract.mint_plant(ptypes::PORTRAIT);
+        contract.harvest_plants(p.vid, &[o.vid]);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Each plant can only help once."#)]
+    fn harvest_with_plants_twice() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let p = contract.mint_plant(ptypes::PORTRAIT);
+        contract.harvest_plants(o.vid, &[p.vid, p.vid]);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Only plants can help with a harvest."#)]
+    fn harvest_with_harvest() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let c = get_grown_context(robert(), env::storage_usage());
+        testing_env!(c);
+        let h = contract.harvest_plant(o.vid);
+        contract.harvest_plants(o.vid, &[h.vid]);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Money Plants can't be harvested."#)]
+    fn harvest_money_plant() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let m = contract.mint_plant(ptypes::MONEY);
+        contract.harvest_plant(m.vid);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"You can only harvest plants you own."#)]
+    fn harvest_not_owner() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let c = get_context(mike(), env::storage_usage());
+        testing_env!(c);
+        contract.harvest_plant(o.vid);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"needed 5000000000000000000000000 yn, received 4000000000000000000000000"#)]
+    fn harvest_price() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let mut c = get_grown_context(robert(), env::storage_usage());
+        c.attached_deposit = to_ynear(4);
+        testing_env!(c);
+        contract.harvest_plant_json(o.vid.into());
+    }
+
+    #[test]
+    fn overpayment_refunded() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let mut c = get_grown_context(robert(), env::storage_usage());
+        c.attached_deposit = to_ynear(5) + 1234;
+        testing_env!(c);
+        contract.harvest_plant_json(o.vid.into());
+        let receipts = near_sdk::serde_json::to_string(&env::created_receipts()).unwrap();
+        assert!(receipts.contains(r#""receiver_id":"robert.testnet","actions":[{"Transfer":{"deposit":1234}}]"#), "excess not refunded: {}", receipts);
+
+        // exact payments get no refund
+        let mut c = get_context(robert(), env::storage_usage());
+        c.attached_deposit = to_ynear(P_PRICES[ptypes::ORACLE as usize]);
+        testing_env!(c);
+        contract.mint_plant_json(ptypes::ORACLE);
+        assert!(env::created_receipts().is_empty(), "refunded nothing");
+    }
+
+    #[test]
+    fn set_prices() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        contract.grant_curator(mike());
+        let c = get_context(mike(), env::storage_usage());
+        testing_env!(c);
+        contract.set_plant_price(ptypes::PORTRAIT, 25.into());
+        contract.set_harvest_price(ptypes::ORACLE, 2.into());
+        contract.set_breed_price(12.into());
+        contract.set_fertilizer_price(0.into());
+        contract.set_random_plant_price(8.into());
+
+        let list = contract.get_price_list();
+        let portrait = list.plants.iter().find(|p| p.ptype == ptypes::PORTRAIT).unwrap();
+        assert_eq!(("Portrait Plant", 25), (portrait.name.as_str(), u128::from(portrait.price)));
+        let fortune = list.harvests.iter().find(|p| p.ptype == ptypes::ORACLE).unwrap();
+        assert_eq!(("Fortune", 2), (fortune.name.as_str(), u128::from(fortune.price)));
+        assert_eq!((12, 0, 8), (u128::from(list.breed), u128::from(list.fertilizer), u128::from(list.random)));
+
+        // and that's what things cost now
+        let mut c = get_context(mike(), env::storage_usage());
+        c.attached_deposit = to_ynear(25);
+        testing_env!(c);
+        let p = contract.mint_plant_json(ptypes::PORTRAIT);
+        let mut c = get_grown_context(mike(), env::storage_usage());
+        c.attached_deposit = to_ynear(0);
+        testing_env!(c);
+        contract.fertilize_plant(p.vid);
+    }
+
+    #[test]
+    fn mint_random_plant() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let odds = contract.get_random_plant_odds();
+        assert_eq!(P_NAMES.len() - 1, odds.len(), "every type has a weight, and can be minted");
+        let oracle = odds.iter().find(|o| o.ptype == ptypes::ORACLE).unwrap();
+        assert_eq!(("Oracle Plant", 3000), (oracle.name.as_str(), oracle.bps));
+
+        let mut c = get_context(robert(), env::storage_usage());
+        c.attached_deposit = to_ynear(RANDOM_PLANT_PRICE);
+        testing_env!(c);
+        let p = contract.mint_random_plant();
+        assert_eq!(vtypes::PLANT, p.vtype);
+        assert!(odds.iter().any(|o| o.ptype == p.vsubtype), "not a type it could be: {}", p.vsubtype);
+
+        // with every other type weighed out, it has to be a money plant
+        for ptype in 1..P_NAMES.len() as PlantType {
+            if ptype != ptypes::MONEY {
+                contract.set_random_plant_weight(ptype, 0);
+            }
+        }
+        assert_eq!(vec![10000], contract.get_random_plant_odds().iter().map(|o| o.bps).collect::<Vec<u32>>());
+        let c = get_context(robert(), env::storage_usage());
+        testing_env!(c);
+        assert_eq!(ptypes::MONEY, contract.mint_random_plant().vsubtype);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"No plants can be minted at random right now."#)]
+    fn mint_random_plant_none_left() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        for ptype in 1..P_NAMES.len() as PlantType {
+            contract.set_random_plant_weight(ptype, 0);
+        }
+        let mut c = get_context(robert(), env::storage_usage());
+        c.attached_deposit = to_ynear(RANDOM_PLANT_PRICE);
+        testing_env!(c);
+        contract.mint_random_plant();
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Prices can be at most 340282366920938 NEAR."#)]
+    fn approve_gene_price_too_high() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let c = get_context(robert(), env::storage_usage());
+        testing_env!(c);
+        let gene_id = contract.submit_gene(gene_spec(vtypes::PLANT, ptypes::MONEY));
+        // (a price this high would wrap around to almost nothing in yoctoNEAR)
+        contract.approve_gene(gene_id, (u128::MAX / 10u128.pow(24) + 1).into(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Only curators can call this method."#)]
+    fn set_price_not_curator() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let c = get_context(joe(), env::storage_usage());
+        testing_env!(c);
+        contract.set_plant_price(ptypes::ORACLE, 0.into());
+    }
+
+    #[test]
+    #[should_panic(expected = r#"This plant can't be harvested while it's a seed; wait until it's mature."#)]
+    fn harvest_too_early() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        contract.harvest_plant(o.vid);
+    }
+
+    // an oracle of average vigor and growth rate: it can be harvested every 6 hours, 3 times a season,
+    // and is mature from 13 to 61 hours after planting, then again from 145
+    fn average_oracle(contract: &mut PlantaryContract) -> Veggie {
+        let mut o = contract.mint_plant(ptypes::ORACLE);
+        o.dna = (8 << 30) | 128;
+        contract.veggies.insert(&o.vid, &o);
+        o
+    }
+
+    fn harvest_at(contract: &mut PlantaryContract, vid: TokenId, hours: u64) -> Veggie {
+        let mut c = get_context(robert(), env::storage_usage());
+        c.block_timestamp = hours * growth::HOUR;
+        testing_env!(c);
+        contract.harvest_plant(vid)
+    }
+
+    #[test]
+    fn harvest_limits() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = average_oracle(&mut contract);
+        for hours in [30, 36, 42] {
+            harvest_at(&mut contract, o.vid, hours);
+        }
+        let status = contract.get_plant_status(o.vid.into());
+        assert_eq!(0, status.harvests_left, "should have used up this season's harvests");
+        assert_eq!(42 * growth::HOUR, u64::from(status.harvested_at));
+        assert_eq!(48 * growth::HOUR, u64::from(status.harvest_ready_at));
+
+        // next season
+        harvest_at(&mut contract, o.vid, 145);
+        assert_eq!(2, contract.get_plant_status(o.vid.into()).harvests_left, "harvests should come back in a new season");
+        assert_eq!(1, contract.get_veggie(o.vid).harvest_season);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"This plant can be harvested again in 300 minutes."#)]
+    fn harvest_cooldown() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = average_oracle(&mut contract);
+        harvest_at(&mut contract, o.vid, 30);
+        harvest_at(&mut contract, o.vid, 31);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"This plant has given all 3 of its harvests this season; it can be harvested again in 5820 minutes."#)]
+    fn harvest_season_limit() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = average_oracle(&mut contract);
+        for hours in [30, 36, 42, 48] {
+            harvest_at(&mut contract, o.vid, hours);
+        }
+    }
+
+    #[test]
+    fn stage_art() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let mut spec = gene_spec(vtypes::PLANT, ptypes::ORACLE);
+        spec.meta_url = "https://arweave.net/mature-oracle".to_string();
+        spec.stage = Some(Stage::Mature);
+        let gene_id = contract.add_gene(spec);
+        assert!(!contract.genome.available(vtypes::PLANT, ptypes::ORACLE).contains(&gene_id.into()), "stage art shouldn't be minted");
+
+        let o = contract.mint_plant(ptypes::ORACLE);
+        assert_eq!(Some(Stage::Seed), o.stage());
+        assert_eq!(None, contract.get_veggie_json(o.vid.into()).stage_meta_url, "no art for seeds");
+
+        let c = get_grown_context(robert(), env::storage_usage());
+        testing_env!(c);
+        let json = contract.get_veggie_json(o.vid.into());
+        assert_eq!(Some(Stage::Mature), json.stage);
+        assert_eq!(Some("https://arweave.net/mature-oracle".to_string()), json.stage_meta_url, "mature art not found");
+        assert_eq!(0, u64::from(json.planted_at));
+    }
+
+    #[test]
+    fn water_plant() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let status = contract.get_plant_status(o.vid.into());
+        assert_eq!((care::FULL, care::FULL), (status.hydration, status.health), "new plants should be fresh");
+
+        let mut c = get_context(robert(), env::storage_usage());
+        c.block_timestamp = 24 * growth::HOUR;
+        testing_env!(c);
+        assert_eq!(50, contract.get_plant_status(o.vid.into()).hydration, "should dry out");
+        let status = contract.water_plant(o.vid.into());
+        assert_eq!(care::FULL, status.hydration, "watering didn't help");
+        assert_eq!(24 * growth::HOUR, u64::from(status.cared_at));
+        assert_eq!(care::FULL, contract.get_veggie_json(o.vid.into()).hydration, "watering not saved");
+    }
+
+    #[test]
+    fn neglected_plant() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let healthy = contract.get_harvest_odds(o.vid.into());
+
+        // a week without water
+        let mut c = get_context(robert(), env::storage_usage());
+        c.block_timestamp = 7 * 24 * growth::HOUR;
+        testing_env!(c.clone());
+        let status = contract.get_plant_status(o.vid.into());
+        assert_eq!(0, status.hydration);
+        assert!(status.health < care::FULL, "neglected plant should lose health");
+        let neglected = contract.get_harvest_odds(o.vid.into());
+        assert!(neglected[4].bps < healthy[4].bps, "neglected plant should have worse odds");
+
+        // fertilizer helps, at a price
+        c.attached_deposit = to_ynear(FERTILIZER_PRICE);
+        testing_env!(c);
+        let fertilized = contract.fertilize_plant(o.vid.into());
+        assert_eq!(status.health + 25, fertilized.health, "fertilizer didn't help");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"You can only look after plants you own."#)]
+    fn water_plant_not_owner() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let c = get_context(mike(), env::storage_usage());
+        testing_env!(c);
+        contract.water_plant(o.vid.into());
+    }
+
+    #[test]
+    fn breed_plants() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let p = contract.mint_plant(ptypes::ORACLE);
+
+        let mut c = get_context(robert(), env::storage_usage());
+        c.attached_deposit = to_ynear(BREED_PRICE);
+        testing_env!(c);
+        let child = contract.breed_plants(o.vid.into(), p.vid.into());
+        assert_eq!(vtypes::PLANT, child.vtype);
+        assert_eq!(ptypes::ORACLE, child.vsubtype, "child should take its parents' type");
+        assert_eq!(TokenJSON::from(o.vid), child.parent);
+        assert_eq!(TokenJSON::from(p.vid), child.co_parent);
+        assert_eq!(1, child.generation);
+        assert_eq!(robert(), contract.token_bank.get_token_owner(child.vid.into()));
+        assert_eq!(BREED_COOLDOWN, contract.get_veggie(o.vid).breed_ready_at, "first parent needs a rest");
+        assert_eq!(BREED_COOLDOWN, contract.get_veggie(p.vid).breed_ready_at, "second parent needs a rest");
+
+        // once they've rested, the parents can breed with their child
+        let mut c = get_context(robert(), env::storage_usage());
+        c.block_timestamp = BREED_COOLDOWN;
+        testing_env!(c);
+        let grandchild = contract.breed(child.vid.into(), p.vid);
+        assert_eq!(2, grandchild.generation, "generation should follow the older parent");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"can breed again in 1440 minutes."#)]
+    fn breed_cooldown() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let p = contract.mint_plant(ptypes::ORACLE);
+        let q = contract.mint_plant(ptypes::ORACLE);
+        contract.breed(o.vid, p.vid);
+        contract.breed(q.vid, o.vid);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Only plants of the same type can breed."#)]
+    fn breed_different_types() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let m = contract.mint_plant(ptypes::MONEY);
+        let o = contract.mint_plant(ptypes::ORACLE);
+        // a Money plant would cost more to mint than to breed
+        contract.breed(m.vid, o.vid);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"You can only breed plants you own."#)]
+    fn breed_not_owner() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let c = get_context(mike(), env::storage_usage());
+        testing_env!(c);
+        let p = contract.mint_plant(ptypes::PORTRAIT);
+        contract.breed(p.vid, o.vid);
+    }
+
+    // a gene with nothing but the required bits
+    fn gene_spec(vtype: VeggieType, vsubtype: VeggieSubType) -> GeneSpec {
+        GeneSpec {
+            vtype,
+            vsubtype,
+            meta_url: "https://arweave.net/test-gene".to_string(),
+            media: None,
+            media_hash: None,
+            title: None,
+            description: None,
+            artist: None,
+            artist_id: None,
+            royalty_bps: None,
+            max_supply: None,
+            price: None,
+            rarity: None,
+            stage: None,
+        }
+    }
+
+    // retire everything else in the gene's pool, so new veggies have to use it.
+    fn only_gene(contract: &mut PlantaryContract, spec: GeneSpec) -> GeneId {
+        for g in contract.get_genes(spec.vtype, spec.vsubtype, 0, 0) {
+            contract.retire_gene(g.gene_id);
+        }
+        contract.add_gene(spec).into()
+    }
+
+    #[test]
+    fn genome_launch_art() {
+        testing_env!(get_context(robert(), 0));
+        let contract = PlantaryContract::new(robert());
+        assert_eq!(4, contract.get_genes(vtypes::PLANT, ptypes::ORACLE, 0, 0).len(), "bad oracle plant pool");
+        assert_eq!(11, contract.get_genes(vtypes::HARVEST, ptypes::ORACLE, 0, 0).len(), "bad oracle harvest pool");
+        assert_eq!(0, contract.get_genes(vtypes::HARVEST, ptypes::MONEY, 0, 0).len(), "money plants have no harvest");
+
+        let page = contract.get_genes(vtypes::HARVEST, ptypes::PORTRAIT, 5, 2);
+        assert_eq!(5, page.len(), "bad page size");
+        assert_eq!(Some("Dennis".to_string()), contract.get_genes(vtypes::HARVEST, ptypes::PORTRAIT, 1, 13)[0].title, "bad gene order");
+    }
+
+    #[test]
+    fn gene_supply() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let mut spec = gene_spec(vtypes::PLANT, ptypes::PORTRAIT);
+        spec.title = Some("Effigia nova".to_string());
+        spec.max_supply = Some(2.into());
+        let gene_id = only_gene(&mut contract, spec);
+
+        let p = contract.mint_plant(ptypes::PORTRAIT);
+        assert_eq!(p.name, p.metadata.title, "plants should be titled with their species name");
+        assert_eq!(Some(2), p.metadata.copies, "copies should be the max supply");
+        assert_eq!("https://arweave.net/test-gene", p.meta_url);
+        contract.mint_plant(ptypes::PORTRAIT);
+
+        let gene = contract.get_gene(gene_id.into());
+        assert_eq!(2, u64::from(gene.minted), "mints not counted");
+        assert!(contract.genome.available(vtypes::PLANT, ptypes::PORTRAIT).is_empty(), "sold-out gene still available");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"No artwork available for Portrait Plant."#)]
+    fn gene_sold_out() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let mut spec = gene_spec(vtypes::PLANT, ptypes::PORTRAIT);
+        spec.max_supply = Some(1.into());
+        only_gene(&mut contract, spec);
+
+        contract.mint_plant(ptypes::PORTRAIT);
+        contract.mint_plant(ptypes::PORTRAIT);
+    }
+
+    #[test]
+    fn update_gene() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let gene_id = only_gene(&mut contract, gene_spec(vtypes::HARVEST, ptypes::ORACLE));
+        let o = contract.mint_plant(ptypes::ORACLE);
+        let c = get_grown_context(robert(), env::storage_usage());
+        testing_env!(c);
+        contract.harvest_plant(o.vid);
+
+        let mut spec = gene_spec(vtypes::HARVEST, ptypes::ORACLE);
+        spec.artist = Some("Oculardelusion".to_string());
+        spec.max_supply = Some(1.into());
+        contract.update_gene(gene_id.into(), spec);
+
+        let gene = contract.get_gene(gene_id.into());
+        assert_eq!(Some("Oculardelusion".to_string()), gene.artist, "update lost");
+        assert_eq!(1, u64::from(gene.minted), "update should keep the mint count");
+        assert_eq!(GeneStatus::Published, gene.status, "update should keep the status");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Max supply can't be less than the number already minted."#)]
+    fn update_gene_below_minted() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let gene_id = only_gene(&mut contract, gene_spec(vtypes::PLANT, ptypes::MONEY));
+        contract.mint_plant(ptypes::MONEY);
+        contract.mint_plant(ptypes::MONEY);
+
+        let mut spec = gene_spec(vtypes::PLANT, ptypes::MONEY);
+        spec.max_supply = Some(1.into());
+        contract.update_gene(gene_id.into(), spec);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Only curators can call this method."#)]
+    fn add_gene_not_owner() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let c = get_context(joe(), env::storage_usage());
+        testing_env!(c);
+        contract.add_gene(gene_spec(vtypes::PLANT, ptypes::MONEY));
+    }
+
+    #[test]
+    fn grant_revoke_curator() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        contract.grant_curator(mike());
+        assert!(contract.is_curator(mike()), "mike should be a curator");
+        assert_eq!(vec![mike()], contract.get_curators());
+
+        let c = get_context(mike(), env::storage_usage());
+        testing_env!(c);
+        contract.add_gene(gene_spec(vtypes::PLANT, ptypes::MONEY));
+
+        let c = get_context(robert(), env::storage_usage());
+        testing_env!(c);
+        contract.revoke_curator(mike());
+        assert!(!contract.is_curator(mike()), "mike should not be a curator any more");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Only contract owner can call this method."#)]
+    fn grant_curator_not_owner() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let c = get_context(joe(), env::storage_usage());
+        testing_env!(c);
+        contract.grant_curator(joe());
+    }
+
+    #[test]
+    fn submit_approve_gene() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        contract.grant_curator(mike());
+
+        // joe the artist submits some art; it waits for a curator.
+        let c = get_context(joe(), env::storage_usage());
+        testing_env!(c);
+        let mut spec = gene_spec(vtypes::PLANT, ptypes::PORTRAIT);
+        spec.price = Some(100.into());
+        let gene_id = contract.submit_gene(spec);
+        let pending = contract.get_pending_genes(0, 0);
+        assert_eq!(1, pending.len(), "submission not pending");
+        assert_eq!(GeneStatus::Pending, pending[0].status);
+        assert_eq!(None, pending[0].price, "artists don't set the price");
+        assert_eq!(Some(joe()), pending[0].artist_id, "artist should default to the submitter");
+        let pool = contract.get_genes(vtypes::PLANT, ptypes::PORTRAIT, 0, 0);
+        assert!(pool.iter().all(|g| g.gene_id != gene_id), "pending gene already in the pool");
+
+        let c = get_context(mike(), env::storage_usage());
+        testing_env!(c);
+        contract.approve_gene(gene_id, 3.into(), Some(10.into()));
+        assert!(contract.get_pending_genes(0, 0).is_empty(), "approved gene still pending");
+        let gene = contract.get_gene(gene_id);
+        assert_eq!(GeneStatus::Published, gene.status);
+        assert_eq!(Some(3), gene.price.map(u128::from), "price not set");
+        assert_eq!(Some(10), gene.max_supply.map(u64::from), "supply not set");
+        assert!(contract.genome.available(vtypes::PLANT, ptypes::PORTRAIT).contains(&gene_id.into()), "approved gene not available");
+
+        // anyone can now mint it, at the curator's price.
+        let mut c = get_context(robert(), env::storage_usage());
+        c.attached_deposit = to_ynear(3);
+        testing_env!(c);
+        let p = contract.mint_gene_json(gene_id);
+        assert_eq!(gene_id, p.gene_id, "minted the wrong gene");
+        assert_eq!(Some(joe()), p.royalty.keys().next().cloned(), "artist should get a royalty");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"needed "#)]
+    fn submit_gene_unpaid() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let mut c = get_context(joe(), env::storage_usage());
+        c.attached_deposit = 0;
+        testing_env!(c);
+        contract.submit_gene(gene_spec(vtypes::PLANT, ptypes::MONEY));
+    }
+
+    #[test]
+    fn submit_gene_storage_refund() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let mut c = get_context(joe(), env::storage_usage());
+        c.attached_deposit = to_ynear(1);
+        testing_env!(c);
+        let storage_before = env::storage_usage();
+        contract.submit_gene(gene_spec(vtypes::PLANT, ptypes::MONEY));
+        let cost = (env::storage_usage() - storage_before) as Balance * STORAGE_PRICE_PER_BYTE;
+        assert!(cost > 0, "the submission should take up storage");
+        let receipts = near_sdk::serde_json::to_string(&env::created_receipts()).unwrap();
+        assert!(receipts.contains(&format!(r#"{{"Transfer":{{"deposit":{}}}}}"#, to_ynear(1) - cost)), "the rest should be refunded: {}", receipts);
+    }
+
+    #[test]
+    fn reject_gene() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let c = get_context(joe(), env::storage_usage());
+        testing_env!(c);
+        let gene_id = contract.submit_gene(gene_spec(vtypes::HARVEST, ptypes::ORACLE));
+
+        let c = get_context(robert(), env::storage_usage());
+        testing_env!(c);
+        contract.reject_gene(gene_id);
+        assert!(contract.get_pending_genes(0, 0).is_empty(), "rejected gene still pending");
+        assert_eq!(GeneStatus::Rejected, contract.get_gene(gene_id).status);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Only curators can call this method."#)]
+    fn approve_gene_not_curator() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let c = get_context(joe(), env::storage_usage());
+        testing_env!(c);
+        let gene_id = contract.submit_gene(gene_spec(vtypes::PLANT, ptypes::MONEY));
+        contract.approve_gene(gene_id, 1.into(), None);
+    }
+
+    #[test]
+    fn nft_transfer_payout() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let mut p = contract.mint_plant(ptypes::ORACLE);
+        p.royalty = [(mike(), 1000)].iter().cloned().collect();
+        contract.veggies.insert(&p.vid, &p);
+
+        let payout = contract.nft_payout(p.vid.into(), 10_000.into(), 10);
+        assert_eq!(2, payout.payout.len());
+        assert_eq!(1_000, u128::from(payout.payout[&mike()]), "artist underpaid");
+        assert_eq!(9_000, u128::from(payout.payout[&robert()]), "owner underpaid");
+
+        // Mike sold it to Joe for Robert
+        let mut c = get_context(robert(), env::storage_usage());
+        c.attached_deposit = to_ynear(1);
+        testing_env!(c);
+        contract.nft_approve(p.vid.into(), mike(), None);
+        let mut c = get_context(mike(), env::storage_usage());
+        c.attached_deposit = 1;
+        testing_env!(c);
+        let sale = contract.nft_transfer_payout(joe(), p.vid.into(), Some(0), None, 10_000.into(), 10);
+        assert_eq!(payout, sale, "sale should pay the previous owner");
+        assert_eq!(joe(), contract.get_token_owner(p.vid), "plant was not transferred");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Too many royalty holders for max_len_payout."#)]
+    fn nft_payout_too_long() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let mut p = contract.mint_plant(ptypes::ORACLE);
+        p.royalty = [(mike(), 1000)].iter().cloned().collect();
+        contract.veggies.insert(&p.vid, &p);
+
+        // mike and robert both need paying
+        contract.nft_payout(p.vid.into(), 10_000.into(), 1);
+    }
+
+    #[test]
+    fn nft_payout_huge_balance() {
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+        let mut p = contract.mint_plant(ptypes::ORACLE);
+        p.royalty = [(mike(), 1000)].iter().cloned().collect();
+        contract.veggies.insert(&p.vid, &p);
+
+        let payout = contract.nft_payout(p.vid.into(), u128::MAX.into(), 10);
+        assert_eq!(u128::MAX / 10, u128::from(payout.payout[&mike()]), "artist's share shouldn't overflow");
+        assert_eq!(u128::MAX - u128::MAX / 10, u128::from(payout.payout[&robert()]), "owner should get the rest");
+    }
+
+    #[test]
+    fn get_veggies_page(){
+        testing_env!(get_context(robert(), 0));
+        let mut contract = PlantaryContract::new(robert());
+
+        // mint 5 plants, 3 of them for someone else
+        for _n in 0..2 {
+            contract.mint_plant(ptypes::MONEY);
+        }
+        let c = get_context(joe(), env::storage_usage());
+        testing_env!(c);
+        contract.mint_plant(ptypes::MONEY);
+        let oracles = [contract.mint_plant(ptypes::ORACLE), contract.mint_plant(ptypes::ORACLE)];
+        // ... and 4 harvests, two from each oracle (with a rest in between)
+        for rest in [0, 8 * growth::HOUR] {
+            let mut c = get_grown_context(joe(), env::storage_usage());
+            c.block_timestamp += rest;
+            testing_env!(c);
+            for o in oracles.iter() {
+                contract.harvest_plant(o.vid);
+            }
+        }
+
+        assert_eq!(9, contract.get_tokens_page(100, 0).len(), "bad token total page size");
+        assert_eq!(9, contract.get_tokens_page(0, 0).len(), "a page size of 0 gets every token");
+        assert_eq!(4, contract.get_tokens_page(5, 1).len(), "bad token end page size");
+
+        assert_eq!(9, contract.get_veggies_page(0, 0, 0).len(), "bad veggie total page size");
+        assert_eq!(5, contract.get_veggies_page(vtypes::PLANT, 10, 0).len(), "bad plant page size");
+        assert_eq!(3, contract.get_veggies_page(vtypes::HARVEST, 3, 0).len(), "bad harvest page size");
+        assert_eq!(1, contract.get_veggies_page(vtypes::HARVEST, 3, 1).len(), "bad harvest end page size");
+        assert_eq!(0, contract.get_veggies_page(vtypes::HARVEST, 3, 2).len(), "bad harvest blank page size");
+
+        // NEP-181 pages carry metadata
+        let tokens = contract.nft_tokens_for_owner(joe(), None, Some(100));
+        assert_eq!(7, tokens.len(), "bad owner token page size");
+        assert!(tokens.iter().all(|t| t.metadata.is_some()), "NEP-181 tokens are missing metadata");
+        assert_eq!(9, contract.nft_tokens(None, None).len(), "bad NEP-181 page size");
+    }
+
     // From here down I've just duplicated the unit tests in TokenBank.rs ,
     // to test our wrapper methods around that object.
 
@@ -0,0 +1,78 @@
+//! Lucky numbers.  A Lucky Numbers plant's harvest holds a set of different numbers,
+//! drawn by an RNG seeded with the harvest's DNA and the block's random seed.
+//! The draw keeps the seed, and how many numbers there were to draw and from what
+//! range, so anyone can draw them again and check (see `draw_lucky_numbers`).
+//! How many numbers, and how high they go, depends on the parent plant's traits.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::env;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::Serialize;
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use rand_seeder::Seeder;
+
+use crate::dna::Traits;
+
+#[derive(PartialEq, Clone, Debug, Serialize, BorshDeserialize, BorshSerialize)]
+pub struct LuckyDraw {
+    pub numbers: Vec<u32>,
+    pub count: u8,          // how many numbers were drawn ...
+    pub max: u32,           // ... from 1 to max
+    pub seed: Base64VecU8,  // the block's random seed
+}
+
+impl LuckyDraw {
+    pub fn new(traits: &Traits, dna: u64, seed: Vec<u8>) -> Self {
+        // 3 to 6 numbers, depending on the plant's yield; up to 40 to 91, depending on its vigor
+        let count = 3 + traits.yields / 64;
+        let max = 40 + traits.vigor as u32 / 5;
+        Self { numbers: draw(dna, &seed, count, max), count, max, seed: Base64VecU8(seed) }
+    }
+}
+
+// `count` different numbers from 1 to `max` (below u32::MAX), smallest first
+pub fn draw(dna: u64, seed: &[u8], count: u8, max: u32) -> Vec<u32> {
+    if count as u32 > max {
+        env::panic(b"Can't draw more numbers than there are.");
+    }
+    // (the draw picks from 1 up to, but not including, max + 1)
+    if max == u32::MAX {
+        env::panic(format!("Lucky numbers can go up to {} at most.", u32::MAX - 1).as_bytes());
+    }
+    let mut rng: ChaCha8Rng = Seeder::from((dna, seed)).make_rng();
+    let mut numbers: Vec<u32> = Vec::new();
+    while numbers.len() < count as usize {
+        let n = rng.gen_range(1, max + 1);
+        if !numbers.contains(&n) {
+            numbers.push(n);
+        }
+    }
+    numbers.sort_unstable();
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_numbers() {
+        let numbers = draw(42, &[1, 2, 3], 6, 49);
+        assert_eq!(6, numbers.len());
+        assert!(numbers.windows(2).all(|w| w[0] < w[1]), "numbers should be different, and sorted: {:?}", numbers);
+        assert!(numbers.iter().all(|n| (1..=49).contains(n)), "numbers out of range: {:?}", numbers);
+        assert_eq!(numbers, draw(42, &[1, 2, 3], 6, 49), "same inputs should draw the same numbers");
+        assert_ne!(numbers, draw(42, &[1, 2, 4], 6, 49), "a different seed should draw different numbers");
+        assert_eq!(vec![1, 2, 3], draw(0, &[], 3, 3));
+    }
+
+    #[test]
+    fn traits_set_the_draw() {
+        let weak = LuckyDraw::new(&Traits::from_dna(0), 1, vec![0]);
+        assert_eq!((3, 40), (weak.count, weak.max));
+        let strong = LuckyDraw::new(&Traits::from_dna(0xffff), 1, vec![0]);
+        assert_eq!((6, 91), (strong.count, strong.max));
+        assert_eq!(strong.numbers, draw(1, &[0], 6, 91));
+    }
+}
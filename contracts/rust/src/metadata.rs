@@ -0,0 +1,83 @@
+//! NEP-177 metadata for the contract and its tokens, as described at:
+//! https://github.com/near/NEPs/blob/master/specs/Standards/NonFungibleToken/Metadata.md
+
+use near_sdk::{env, json_types::Base64VecU8};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::Serialize;
+
+use crate::constants::{NFT_NAME, NFT_SYMBOL, NFT_ICON, NFT_BASE_URI};
+use crate::genome::Gene;
+
+pub const NFT_METADATA_SPEC: &str = "nft-1.0.0";
+
+pub trait NEP177 {
+    // Get metadata describing the whole contract.
+    fn nft_metadata(&self) -> NFTContractMetadata;
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize)]
+pub struct NFTContractMetadata {
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    pub icon: Option<String>,
+    pub base_uri: Option<String>,
+    pub reference: Option<String>,
+}
+
+impl NFTContractMetadata {
+    pub fn plantary() -> Self {
+        Self {
+            spec: NFT_METADATA_SPEC.to_string(),
+            name: NFT_NAME.to_string(),
+            symbol: NFT_SYMBOL.to_string(),
+            icon: Some(NFT_ICON.to_string()),
+            base_uri: Some(NFT_BASE_URI.to_string()),
+            reference: None,
+        }
+    }
+}
+
+// per-token metadata.  (some of these are strings in the spec even when they hold numbers.)
+#[derive(PartialEq, Clone, Debug, Serialize, BorshDeserialize, BorshSerialize)]
+pub struct TokenMetadata {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub media: Option<String>,
+    pub media_hash: Option<Base64VecU8>,
+    pub copies: Option<u64>,
+    pub issued_at: Option<String>, // unix epoch in milliseconds
+    pub extra: Option<String>,     // JSON, e.g. the artist credit
+    pub reference: Option<String>, // the artwork's JSON on arweave
+}
+
+impl TokenMetadata {
+    // metadata for a veggie newly minted from the given gene.
+    // default_title is used when we don't know the artwork's own name.
+    pub fn for_gene(gene: &Gene, default_title: &str) -> Self {
+        Self {
+            title: Some(gene.title.clone().unwrap_or_else(|| default_title.to_string())),
+            description: gene.description.clone(),
+            media: gene.media.clone(),
+            media_hash: gene.media_hash.clone(),
+            copies: gene.max_supply,
+            issued_at: Some((env::block_timestamp() / 1_000_000).to_string()),
+            extra: gene.artist.as_ref().map(|a| near_sdk::serde_json::json!({ "artist": a }).to_string()),
+            reference: Some(gene.meta_url.clone()),
+        }
+    }
+
+    // metadata for a veggie with no artwork but what's drawn from its DNA (which nft_token fills in as media)
+    pub fn drawn(title: &str) -> Self {
+        Self {
+            title: Some(title.to_string()),
+            description: None,
+            media: None,
+            media_hash: None,
+            copies: None,
+            issued_at: Some((env::block_timestamp() / 1_000_000).to_string()),
+            extra: None,
+            reference: None,
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Upgrading from the first deployed version.  Its state is laid out as the `Old` structs
+//! here: a token bank with no approvals or ordered indexes (and owner and access sets that
+//! all shared one storage prefix), veggies with only a type, parent, DNA and artwork URL,
+//! and no genome, curators or prices.  `migrate` reads that state and writes it back in
+//! the current layout, in one call.
+//!
+//! Old veggies are matched to the launch genes by their artwork URL.  Since they were
+//! minted before growth and care existed, they count as planted (and watered) at the upgrade.
+
+use near_sdk::collections::{UnorderedMap, UnorderedSet};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{env, AccountId};
+
+use crate::constants::{vtypes, VeggieType, VeggieSubType, H_NAMES, P_NAMES};
+use crate::genome::{Genome, NO_GENE};
+use crate::metadata::TokenMetadata;
+use crate::prices::Prices;
+use crate::token_bank::{AccountIdHash, TokenBank, TokenId, TokenSet};
+use crate::{names, text, PlantaryContract, Veggie};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OldTokenBank {
+    pub token_to_account: UnorderedMap<TokenId, AccountId>,
+    pub account_to_tokens: UnorderedMap<AccountId, TokenSet>,
+    pub account_gives_access: UnorderedMap<AccountIdHash, UnorderedSet<AccountIdHash>>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OldVeggie {
+    pub vid: TokenId,
+    pub vtype: VeggieType,
+    pub vsubtype: VeggieSubType,
+    pub parent: TokenId,
+    pub dna: u64,
+    pub meta_url: String,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OldPlantaryContract {
+    pub token_bank: OldTokenBank,
+    pub owner_id: AccountId,
+    pub veggies: UnorderedMap<TokenId, OldVeggie>,
+}
+
+impl OldTokenBank {
+    fn upgrade(self) -> TokenBank {
+        // (approvals and the ordered indexes start out empty; migrate_shared_sets fills in the indexes)
+        let mut bank = TokenBank {
+            token_to_account: self.token_to_account,
+            account_to_tokens: self.account_to_tokens,
+            account_gives_access: self.account_gives_access,
+            ..TokenBank::new()
+        };
+        bank.migrate_shared_sets();
+        bank
+    }
+}
+
+impl OldVeggie {
+    fn upgrade(self, genome: &mut Genome) -> Veggie {
+        let names = if self.vtype == vtypes::PLANT { &P_NAMES[..] } else { &H_NAMES[..] };
+        let default_title = names.get(self.vsubtype as usize).copied().unwrap_or_default();
+        let gene = genome.find(self.vtype, self.vsubtype, &self.meta_url).map(|gene_id| (gene_id, genome.record_mint(gene_id)));
+        let metadata = match &gene {
+            Some((_, gene)) => TokenMetadata::for_gene(gene, default_title),
+            None => TokenMetadata { reference: Some(self.meta_url.clone()).filter(|url| !url.is_empty()), ..TokenMetadata::drawn(default_title) },
+        };
+
+        let mut v = Veggie::new(self.vid, self.parent, self.vtype, self.vsubtype, self.dna, &self.meta_url, metadata);
+        v.gene_id = NO_GENE;
+        if let Some((gene_id, gene)) = gene {
+            v.royalty = gene.royalty();
+            v.gene_id = gene_id;
+            v.rarity = gene.rarity;
+        }
+        v.planted_at = env::block_timestamp();
+        v.cared_at = v.planted_at;
+        if v.vtype == vtypes::PLANT {
+            v.name = Some(names::binomial(v.vsubtype, v.dna));
+            v.metadata.title = v.name.clone();
+        } else {
+            v.text = text::grammar(v.vsubtype).map(|g| g.generate(v.dna));
+        }
+        v
+    }
+}
+
+impl OldPlantaryContract {
+    pub fn upgrade(self) -> PlantaryContract {
+        let mut genome = Genome::launched();
+
+        // the veggies stay where they are in storage; only their values change layout.
+        // (raw, since the value each replaces is in the old layout)
+        let mut veggies: UnorderedMap<TokenId, Veggie> = UnorderedMap::try_from_slice(&self.veggies.try_to_vec().unwrap()).unwrap();
+        for (vid, old) in self.veggies.iter() {
+            veggies.insert_raw(&vid.try_to_vec().unwrap(), &old.upgrade(&mut genome).try_to_vec().unwrap());
+        }
+
+        PlantaryContract {
+            token_bank: self.token_bank.upgrade(),
+            owner_id: self.owner_id,
+            veggies,
+            genome,
+            curators: UnorderedSet::new(b"curators".to_vec()),
+            prices: Prices::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::{testing_env, MockedBlockchain, VMContext};
+    use crate::constants::{ptypes, P_ART, H_ART};
+    use crate::token_bank::{NEP171, NEP181};
+    use crate::Veggies;
+
+    fn robert() -> AccountId { "robert.testnet".to_string() }
+    fn joe() -> AccountId { "joe.testnet".to_string() }
+
+    fn get_context(predecessor_account_id: AccountId, storage_usage: u64) -> VMContext {
+        VMContext {
+            current_account_id: "plantary.testnet".to_string(),
+            signer_account_id: predecessor_account_id.clone(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id,
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 1_000_000_000,
+            account_balance: 0,
+            account_locked_balance: 0,
+            storage_usage,
+            attached_deposit: 0,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 19,
+        }
+    }
+
+    // state the way the first version wrote it: every owner's token set under one shared prefix
+    fn old_state() -> OldPlantaryContract {
+        let mut old = OldPlantaryContract {
+            token_bank: OldTokenBank {
+                token_to_account: UnorderedMap::new(b"token-belongs-to".to_vec()),
+                account_to_tokens: UnorderedMap::new(b"account-owns".to_vec()),
+                account_gives_access: UnorderedMap::new(b"gives-access".to_vec()),
+            },
+            owner_id: robert(),
+            veggies: UnorderedMap::new(b"veggies".to_vec()),
+        };
+        let veggies = [
+            (11, vtypes::PLANT, ptypes::ORACLE, 0, P_ART[0].1.meta_url, robert()),
+            (12, vtypes::HARVEST, ptypes::ORACLE, 11, H_ART[0].1.meta_url, robert()),
+            (13, vtypes::PLANT, ptypes::MONEY, 0, "https://arweave.net/unknown", joe()),
+        ];
+        for (vid, vtype, vsubtype, parent, meta_url, owner_id) in veggies.iter() {
+            old.veggies.insert(vid, &OldVeggie { vid: *vid, vtype: *vtype, vsubtype: *vsubtype, parent: *parent, dna: 42, meta_url: meta_url.to_string() });
+            let mut tokens = old.token_bank.account_to_tokens.get(owner_id).unwrap_or_else(|| TokenSet::new(b"owner-tokens-set".to_vec()));
+            tokens.insert(vid);
+            old.token_bank.account_to_tokens.insert(owner_id, &tokens);
+            old.token_bank.token_to_account.insert(vid, owner_id);
+        }
+        old
+    }
+
+    #[test]
+    fn migrate_old_state() {
+        testing_env!(get_context(robert(), 0));
+        env::state_write(&old_state());
+        let c = get_context(robert(), env::storage_usage());
+        testing_env!(c);
+        let mut contract = PlantaryContract::migrate();
+        env::state_write(&contract);
+        let contract_read: PlantaryContract = env::state_read().expect("The migrated state doesn't read back.");
+        assert_eq!(robert(), contract_read.owner_id);
+
+        let oracle = contract.get_veggie_json(11.into());
+        assert_eq!(0, oracle.gene_id.0, "should be matched to its launch gene");
+        assert_eq!(oracle.name, oracle.metadata.title, "plants should get their species name");
+        assert_eq!(1_000_000_000, oracle.planted_at.0, "should count as planted at the upgrade");
+        let fortune = contract.get_veggie_json(12.into());
+        assert_eq!(11, fortune.parent.0);
+        assert!(fortune.text.is_some(), "fortunes should get their text");
+        let money = contract.get_veggie_json(13.into());
+        assert_eq!(NO_GENE, money.gene_id.0, "unknown art has no gene");
+        assert_eq!(Some("https://arweave.net/unknown".to_string()), money.metadata.reference);
+
+        // the token bank works again, each owner's tokens under their own prefix
+        assert_eq!(vec![11, 12], contract.token_bank.owner_tokens_after(&robert(), None).collect::<Vec<TokenId>>());
+        assert_eq!(3, contract.token_bank.nft_tokens(None, None).len());
+        let mut c = get_context(joe(), env::storage_usage());
+        c.attached_deposit = 1;
+        testing_env!(c);
+        contract.token_bank.nft_transfer(robert(), 13.into(), None, None);
+        assert_eq!(3, contract.token_bank.get_owner_tokens(&robert()).len());
+        assert_eq!(1, contract.genome.get(0).minted, "migrated veggies should count toward their gene's supply");
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Only contract owner can call this method."#)]
+    fn migrate_not_owner() {
+        testing_env!(get_context(robert(), 0));
+        env::state_write(&old_state());
+        let c = get_context(joe(), env::storage_usage());
+        testing_env!(c);
+        PlantaryContract::migrate();
+    }
+}
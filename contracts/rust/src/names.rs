@@ -0,0 +1,71 @@
+//! Species names.  Every plant gets a Latin(ish) binomial name, like the artists'
+//! "Phytia perolatta": a genus built from its type's syllables, and a species from
+//! syllables everyone shares.  The syllables are picked by an RNG seeded with the
+//! plant's DNA, so the same plant always has the same name.
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use rand_seeder::Seeder;
+
+use crate::constants::{ptypes, PlantType};
+
+// the start of each type's genus names
+fn genus_starts(ptype: PlantType) -> &'static [&'static str] {
+    match ptype {
+        ptypes::ORACLE => &["Phyt", "Fortun", "Vatic", "Augur", "Sibyl", "Omin", "Delph"],
+        ptypes::PORTRAIT => &["Effig", "Imag", "Vult", "Pict", "Icon", "Speci"],
+        ptypes::MONEY => &["Pecun", "Aur", "Monet", "Denar", "Lucr", "Opul"],
+        ptypes::COMPLIMENT => &["Laud", "Bland", "Grat", "Amabil", "Dulc"],
+        ptypes::INSULT => &["Convic", "Contum", "Mordac", "Acerb", "Spin"],
+        ptypes::SEED => &["Semin", "Germin", "Spor", "Prol", "Gran"],
+        ptypes::ADVICE => &["Consil", "Monit", "Sapient", "Prudent", "Doct"],
+        ptypes::LUCKY => &["Fortuit", "Sort", "Felic", "Prosper", "Numer"],
+        _ => &["Plant", "Herb", "Flor"],
+    }
+}
+
+const GENUS_ENDS: [&str; 8] = ["ia", "a", "ella", "ium", "us", "atta", "ara", "ina"];
+const SPECIES_STARTS: [&str; 16] = [
+    "pero", "glu", "ele", "magn", "vir", "rubr", "alb", "nigr",
+    "flor", "long", "brev", "dulc", "sylv", "mont", "aqu", "nov",
+];
+const SPECIES_ENDS: [&str; 12] = [
+    "latta", "tonis", "gans", "ensis", "ifolia", "iflora", "ata", "osa", "ica", "um", "ella", "ides",
+];
+
+fn pick(words: &[&'static str], rng: &mut impl Rng) -> &'static str {
+    words[rng.gen_range(0, words.len())]
+}
+
+// the name of a plant of this type with this DNA, e.g. "Fortunatta glutonis"
+pub fn binomial(ptype: PlantType, dna: u64) -> String {
+    let mut rng: ChaCha8Rng = Seeder::from(dna).make_rng();
+    let genus = format!("{}{}", pick(genus_starts(ptype), &mut rng), pick(&GENUS_ENDS, &mut rng));
+    let species = format!("{}{}", pick(&SPECIES_STARTS, &mut rng), pick(&SPECIES_ENDS, &mut rng));
+    format!("{} {}", genus, species)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_from_dna() {
+        assert_eq!(binomial(ptypes::ORACLE, 7), binomial(ptypes::ORACLE, 7), "same DNA should give the same name");
+        let names: Vec<String> = (0..20).map(|dna| binomial(ptypes::ORACLE, dna)).collect();
+        assert!(names.iter().any(|n| *n != names[0]), "different DNA should give different names");
+        for name in names {
+            let words: Vec<&str> = name.split(' ').collect();
+            assert_eq!(2, words.len(), "not a binomial: {}", name);
+            assert!(words[0].starts_with(char::is_uppercase) && words[1].starts_with(char::is_lowercase), "badly capitalized: {}", name);
+        }
+    }
+
+    #[test]
+    fn genus_from_type() {
+        for dna in 0..20 {
+            let name = binomial(ptypes::MONEY, dna);
+            assert!(genus_starts(ptypes::MONEY).iter().any(|s| name.starts_with(s)), "{} isn't a money plant", name);
+        }
+    }
+}
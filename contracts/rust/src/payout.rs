@@ -0,0 +1,56 @@
+//! NEP-199 royalties and payouts, as described at:
+//! https://github.com/near/NEPs/blob/master/specs/Standards/NonFungibleToken/Payout.md
+
+use near_sdk::{env, json_types::U128, AccountId};
+use near_sdk::serde::Serialize;
+use std::collections::HashMap;
+
+use crate::token_bank::TokenJSON;
+
+// royalties are in basis points: 10000 == 100%
+pub const ONE_HUNDRED_PERCENT_BPS: u32 = 10_000;
+
+/// Who is owed what from a sale: account -> basis points
+pub type Royalty = HashMap<AccountId, u32>;
+
+pub trait NEP199 {
+    // How a sale of `token_id` for `balance` should be split up.
+    // Panics if that would take more than `max_len_payout` payments.
+    fn nft_payout(&self, token_id: TokenJSON, balance: U128, max_len_payout: u32) -> Payout;
+
+    // Transfer the token (like `nft_transfer`), and return the payout for the sale.
+    fn nft_transfer_payout(&mut self, receiver_id: AccountId, token_id: TokenJSON, approval_id: Option<u64>, memo: Option<String>, balance: U128, max_len_payout: u32) -> Payout;
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize)]
+pub struct Payout {
+    pub payout: HashMap<AccountId, U128>,
+}
+
+impl Payout {
+    // royalty holders get their share; the owner gets whatever is left.
+    pub fn for_sale(royalty: &Royalty, owner_id: &AccountId, balance: u128, max_len_payout: u32) -> Self {
+        let mut payees = royalty.len() as u32;
+        if !royalty.contains_key(owner_id) {
+            payees += 1;
+        }
+        if payees > max_len_payout {
+            env::panic(b"Too many royalty holders for max_len_payout.")
+        }
+
+        let mut shares: HashMap<AccountId, u128> = HashMap::new();
+        let mut paid: u128 = 0;
+        for (account_id, bps) in royalty {
+            // (balance * bps / 100%, split up so that it can't overflow)
+            let (whole, rest) = (balance / ONE_HUNDRED_PERCENT_BPS as u128, balance % ONE_HUNDRED_PERCENT_BPS as u128);
+            let share = whole * *bps as u128 + rest * *bps as u128 / ONE_HUNDRED_PERCENT_BPS as u128;
+            paid += share;
+            shares.insert(account_id.clone(), share);
+        }
+        *shares.entry(owner_id.clone()).or_insert(0) += balance - paid;
+
+        Self {
+            payout: shares.into_iter().map(|(account_id, share)| (account_id, share.into())).collect(),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! What things cost, in whole NEAR.  Prices live in the contract's state, so the
+//! owner and curators can change them without a redeploy; they start out as the
+//! ones in constants.rs.  (An artwork minted by its gene has the gene's own price.)
+//! A random plant has a price of its own, and weights for how likely each type is.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::Balance;
+
+use crate::constants::{PlantType, BREED_PRICE, FERTILIZER_PRICE, H_NAMES, H_POLICY, P_NAMES, P_PRICES, RANDOM_PLANT_PRICE, RANDOM_WEIGHTS};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Prices {
+    plants: Vec<Balance>,   // to mint a plant, by PlantType
+    harvests: Vec<Balance>, // to harvest a plant, by PlantType
+    random_weights: Vec<u32>, // how likely a random plant is to be each PlantType
+    pub random: Balance,      // to mint a random plant
+    pub breed: Balance,
+    pub fertilizer: Balance,
+}
+
+// prices are in whole NEAR, and are charged in yoctoNEAR, which has to fit in a u128
+const YOCTO_PER_NEAR: Balance = 1_000_000_000_000_000_000_000_000;
+pub const MAX_PRICE: Balance = Balance::MAX / YOCTO_PER_NEAR;
+
+// panic unless the price can be charged
+pub fn check_price(price: Balance) -> Balance {
+    if price > MAX_PRICE {
+        panic!("Prices can be at most {} NEAR.", MAX_PRICE);
+    }
+    price
+}
+
+pub fn to_yocto(price: Balance) -> Balance {
+    check_price(price) * YOCTO_PER_NEAR
+}
+
+impl Prices {
+    pub fn new() -> Self {
+        Self {
+            plants: P_PRICES.to_vec(),
+            harvests: H_POLICY.iter().map(|p| p.price).collect(),
+            random_weights: RANDOM_WEIGHTS.to_vec(),
+            random: RANDOM_PLANT_PRICE,
+            breed: BREED_PRICE,
+            fertilizer: FERTILIZER_PRICE,
+        }
+    }
+
+    pub fn plant(&self, ptype: PlantType) -> Balance {
+        self.plants[check_ptype(ptype)]
+    }
+
+    pub fn harvest(&self, ptype: PlantType) -> Balance {
+        self.harvests[check_ptype(ptype)]
+    }
+
+    pub fn set_plant(&mut self, ptype: PlantType, price: Balance) {
+        self.plants[check_ptype(ptype)] = check_price(price);
+    }
+
+    pub fn set_harvest(&mut self, ptype: PlantType, price: Balance) {
+        if !H_POLICY[check_ptype(ptype)].harvestable {
+            panic!("{}s can't be harvested.", P_NAMES[ptype as usize]);
+        }
+        self.harvests[ptype as usize] = check_price(price);
+    }
+
+    pub fn random_weight(&self, ptype: PlantType) -> u32 {
+        self.random_weights[check_ptype(ptype)]
+    }
+
+    pub fn set_random_weight(&mut self, ptype: PlantType, weight: u32) {
+        self.random_weights[check_ptype(ptype)] = weight;
+    }
+
+    pub fn list(&self) -> PriceList {
+        let price = |t: usize, name: &str, price: Balance| TypePrice { ptype: t as PlantType, name: name.to_string(), price: price.into() };
+        PriceList {
+            plants: (1..P_NAMES.len()).map(|t| price(t, P_NAMES[t], self.plants[t])).collect(),
+            harvests: (1..P_NAMES.len())
+                .filter(|t| H_POLICY[*t].harvestable)
+                .map(|t| price(t, H_NAMES[t], self.harvests[t]))
+                .collect(),
+            random: self.random.into(),
+            breed: self.breed.into(),
+            fertilizer: self.fertilizer.into(),
+        }
+    }
+}
+
+// panic unless it's a plant type there's a price for
+fn check_ptype(ptype: PlantType) -> usize {
+    if ptype == 0 || ptype as usize >= P_NAMES.len() {
+        panic!("Unknown plant type {}.", ptype);
+    }
+    ptype as usize
+}
+
+// every price, in NEAR, for get_price_list
+#[derive(PartialEq, Clone, Debug, Serialize)]
+pub struct PriceList {
+    pub plants: Vec<TypePrice>,   // to mint a plant of each type
+    pub harvests: Vec<TypePrice>, // to harvest a plant of each type (named after the harvest)
+    pub random: U128,             // to mint a random plant
+    pub breed: U128,
+    pub fertilizer: U128,
+}
+
+#[derive(PartialEq, Clone, Debug, Serialize)]
+pub struct TypePrice {
+    pub ptype: PlantType,
+    pub name: String,
+    pub price: U128,
+}
+
+// how likely a random plant is to be of a type, in basis points (see rarity::ALL_ODDS)
+#[derive(PartialEq, Clone, Debug, Serialize)]
+pub struct TypeOdds {
+    pub ptype: PlantType,
+    pub name: String,
+    pub bps: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ptypes;
+
+    #[test]
+    fn starting_prices() {
+        let prices = Prices::new();
+        assert_eq!(P_PRICES[ptypes::ORACLE as usize], prices.plant(ptypes::ORACLE));
+        assert_eq!(H_POLICY[ptypes::SEED as usize].price, prices.harvest(ptypes::SEED));
+        let list = prices.list();
+        assert_eq!(P_NAMES.len() - 1, list.plants.len(), "every plant type should have a price");
+        assert!(list.plants.iter().all(|p| u128::from(p.price) > 0), "no plant should start out free");
+        assert!(list.harvests.iter().all(|p| p.ptype != ptypes::MONEY), "money plants can't be harvested");
+    }
+
+    #[test]
+    fn set_prices() {
+        let mut prices = Prices::new();
+        prices.set_plant(ptypes::PORTRAIT, 25);
+        prices.set_harvest(ptypes::ORACLE, 2);
+        assert_eq!((25, 2), (prices.plant(ptypes::PORTRAIT), prices.harvest(ptypes::ORACLE)));
+        assert_eq!(P_PRICES[ptypes::ORACLE as usize], prices.plant(ptypes::ORACLE), "other prices shouldn't change");
+        prices.set_random_weight(ptypes::MONEY, 0);
+        assert_eq!((0, RANDOM_WEIGHTS[ptypes::ORACLE as usize]), (prices.random_weight(ptypes::MONEY), prices.random_weight(ptypes::ORACLE)));
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Prices can be at most 340282366920938 NEAR."#)]
+    fn price_too_high() {
+        Prices::new().set_plant(ptypes::ORACLE, MAX_PRICE + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Money Plants can't be harvested."#)]
+    fn price_unharvestable() {
+        Prices::new().set_harvest(ptypes::MONEY, 5);
+    }
+}
@@ -0,0 +1,116 @@
+//! Rarity tiers, and the odds of a harvest landing in each.
+//! Every gene belongs to a tier.  A harvest first draws its tier, with odds
+//! that shift toward the rare end the stronger the parent plant's DNA is
+//! (and back again, the less healthy it is), and then draws its artwork
+//! from the genes in that tier.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::care::FULL;
+use crate::dna::Traits;
+
+// odds are in basis points: 10000 == certain
+pub const ALL_ODDS: u32 = 10_000;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug, Default, Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rarity {
+    #[default]
+    Common,    // standard harvest
+    Rare,      // special harvest
+    UltraRare, // power harvest
+    Legendary, // unique harvest
+    Mythic,    // magic harvest
+}
+
+pub const TIERS: [Rarity; 5] = [Rarity::Common, Rarity::Rare, Rarity::UltraRare, Rarity::Legendary, Rarity::Mythic];
+
+// the odds of each tier for the weakest possible plant
+const BASE_ODDS: [u32; 5] = [6000, 2500, 1000, 400, 100];
+
+pub type Odds = [u32; 5];
+
+#[derive(PartialEq, Clone, Debug, Serialize)]
+pub struct TierOdds {
+    pub rarity: Rarity,
+    pub bps: u32,
+}
+
+// the most a plant's traits can add to its harvest odds
+const MAX_BOOST: u32 = 255 + 255 / 4;
+
+// A plant's rarity affinity (and, a little, its vigor) multiplies the odds of
+// each rarer tier, the rarest most; common gets whatever is left.
+// At full strength, mythic is three times as likely as at none.
+// Poor health takes that away again: a plant with none left has half the odds it would at full health.
+pub fn harvest_odds(traits: &Traits, health: u8) -> Odds {
+    let boost = traits.rarity_affinity as u32 + traits.vigor as u32 / 4;
+    let health = health.min(FULL) as u32;
+    let mut odds = [0; 5];
+    let mut rarer = 0;
+    for tier in 1..TIERS.len() {
+        odds[tier] = BASE_ODDS[tier] * (2 * MAX_BOOST + boost * tier as u32) / (2 * MAX_BOOST)
+            * (FULL as u32 + health) / (2 * FULL as u32);
+        rarer += odds[tier];
+    }
+    odds[0] = ALL_ODDS - rarer;
+    odds
+}
+
+// the tier that a roll in 0..ALL_ODDS lands in
+pub fn draw(odds: &Odds, roll: u32) -> Rarity {
+    let mut bound = 0;
+    for (tier, bps) in TIERS.iter().zip(odds.iter()) {
+        bound += bps;
+        if roll < bound {
+            return *tier;
+        }
+    }
+    Rarity::Common
+}
+
+pub fn odds_json(odds: &Odds) -> Vec<TierOdds> {
+    TIERS.iter().zip(odds.iter()).map(|(rarity, bps)| TierOdds { rarity: *rarity, bps: *bps }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn odds_add_up() {
+        for dna in [0, !0, 0x1234_5678_9abc_def0].iter() {
+            let odds = harvest_odds(&Traits::from_dna(*dna), FULL);
+            assert_eq!(ALL_ODDS, odds.iter().sum::<u32>(), "odds don't add up for {:x}", dna);
+        }
+        assert_eq!(BASE_ODDS, harvest_odds(&Traits::from_dna(0), FULL), "weakest plant should get the base odds");
+    }
+
+    #[test]
+    fn affinity_favors_rare() {
+        let weak = harvest_odds(&Traits::from_dna(0), FULL);
+        let strong = harvest_odds(&Traits::from_dna(!0), FULL);
+        assert!(strong[0] < weak[0], "strong plant should get fewer commons");
+        assert_eq!(3 * weak[4], strong[4], "strongest plant should get three times the mythics");
+    }
+
+    #[test]
+    fn neglect_favors_common() {
+        let traits = Traits::from_dna(!0);
+        let healthy = harvest_odds(&traits, FULL);
+        let neglected = harvest_odds(&traits, 0);
+        assert!(neglected[0] > healthy[0], "neglected plant should get more commons");
+        assert_eq!(healthy[4] / 2, neglected[4], "neglected plant should get half the mythics");
+    }
+
+    #[test]
+    fn draw_tiers() {
+        let odds = BASE_ODDS;
+        assert_eq!(Rarity::Common, draw(&odds, 0));
+        assert_eq!(Rarity::Common, draw(&odds, 5999));
+        assert_eq!(Rarity::Rare, draw(&odds, 6000));
+        assert_eq!(Rarity::Legendary, draw(&odds, 9899));
+        assert_eq!(Rarity::Mythic, draw(&odds, 9999));
+    }
+}
@@ -0,0 +1,143 @@
+//! Artwork drawn from DNA.  Every veggie can be drawn as a small SVG: a plant as a
+//! stem with leaves (and flowers, while it's flowering) in a pot, a harvest as a
+//! fruit.  The drawing only depends on the veggie's DNA and growth stage, so it's
+//! the same every time, and a veggie has art even if no artist has made any for it.
+//!
+//! SVGs use single quotes, so that they can go in a `data:` URI with little escaping.
+
+use crate::constants::{vtypes, VeggieType};
+use crate::dna::{Traits, COLORS, LEAF_SHAPES};
+use crate::growth::Stage;
+
+// the colors of dna::COLORS
+const PALETTE: [&str; 8] = [
+    "#5a9e3a", "#e8c547", "#e8873a", "#c8403a", "#8a4fb0", "#4a7fc8", "#f2f2ec", "#2b2b2b",
+];
+const LEAF: &str = "#4f8f3a";
+const DRY_LEAF: &str = "#a08850";
+const STEM: &str = "#385723";
+const POT: &str = "#b5653a";
+const SOIL: &str = "#5b3a24";
+
+// (half width, half length) of each of dna::LEAF_SHAPES
+const LEAF_SIZES: [(u32, u32); 8] = [(9, 9), (6, 11), (9, 8), (4, 13), (2, 14), (12, 6), (10, 10), (8, 8)];
+
+fn color(traits: &Traits) -> &'static str {
+    PALETTE[COLORS.iter().position(|c| *c == traits.color).unwrap_or(0)]
+}
+
+fn leaf_size(traits: &Traits) -> (u32, u32) {
+    LEAF_SIZES[LEAF_SHAPES.iter().position(|s| *s == traits.leaf_shape).unwrap_or(0)]
+}
+
+// an SVG of a veggie (with its growth stage, for plants)
+pub fn svg(vtype: VeggieType, dna: u64, stage: Option<Stage>) -> String {
+    let traits = Traits::from_dna(dna);
+    let body = if vtype == vtypes::PLANT {
+        plant(&traits, dna, stage.unwrap_or(Stage::Seed))
+    } else {
+        fruit(&traits)
+    };
+    format!("<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 100 100'>{}</svg>", body)
+}
+
+fn plant(traits: &Traits, dna: u64, stage: Stage) -> String {
+    let mut svg = format!(
+        "<path d='M25 70h50l-6 26H31z' fill='{}'/><ellipse cx='50' cy='70' rx='25' ry='4' fill='{}'/>",
+        POT, SOIL
+    );
+    let height = match stage {
+        Stage::Seed => {
+            svg.push_str("<ellipse cx='50' cy='69' rx='4' ry='2.5' fill='#c9a66b'/>");
+            return svg;
+        }
+        Stage::Sprout => 20,
+        Stage::Wilting => 50,
+        Stage::Mature | Stage::Flowering => 60,
+    };
+    // the stem leans a little, one way or the other, depending on the DNA's spare bits
+    let lean = ((dna >> 40) % 21) as i32 - 10;
+    let (top_x, top_y) = (50 + lean, 70 - height);
+    svg.push_str(&format!(
+        "<path d='M50 70Q{} {} {} {}' stroke='{}' stroke-width='3' fill='none'/>",
+        50 - lean, 70 - height / 2, top_x, top_y, STEM
+    ));
+
+    // vigorous plants have more leaves
+    let pairs = if stage == Stage::Sprout { 1 } else { 2 + traits.vigor as i32 / 64 };
+    let (width, length) = leaf_size(traits);
+    let (fill, droop) = if stage == Stage::Wilting { (DRY_LEAF, 40) } else { (LEAF, 0) };
+    for i in 0..pairs {
+        let y = 70 - height * (i + 1) / (pairs + 1);
+        let x = 50 + lean * (70 - y) / height;
+        for (side, angle) in [(-1, -50 + droop), (1, 50 - droop)] {
+            svg.push_str(&format!(
+                "<ellipse cx='{}' cy='{}' rx='{}' ry='{}' fill='{}' transform='rotate({} {} {})'/>",
+                x + side * length as i32, y, width, length, fill, angle, x, y
+            ));
+        }
+    }
+
+    if stage == Stage::Flowering {
+        // high-yield plants flower more
+        let flowers = 1 + traits.yields as i32 / 86;
+        for f in 0..flowers {
+            let (x, y) = (top_x + (f - flowers / 2) * 12, top_y + (f % 2) * 6);
+            svg.push_str(&format!("<circle cx='{}' cy='{}' r='7' fill='{}'/>", x, y, color(traits)));
+            svg.push_str(&format!("<circle cx='{}' cy='{}' r='2.5' fill='#f5d76e'/>", x, y));
+        }
+    }
+    svg
+}
+
+fn fruit(traits: &Traits) -> String {
+    let (width, length) = leaf_size(traits);
+    format!(
+        "<circle cx='50' cy='58' r='{}' fill='{}'/><ellipse cx='{}' cy='{}' rx='{}' ry='{}' fill='{}' transform='rotate(40 50 30)'/>",
+        20 + traits.yields as u32 / 16, color(traits), 50 + length, 30, width, length, LEAF
+    )
+}
+
+// an SVG as a data: URI, e.g. for token metadata's `media`
+pub fn data_uri(svg: &str) -> String {
+    let mut uri = String::from("data:image/svg+xml,");
+    for c in svg.chars() {
+        match c {
+            '<' => uri.push_str("%3C"),
+            '>' => uri.push_str("%3E"),
+            '#' => uri.push_str("%23"),
+            '%' => uri.push_str("%25"),
+            '"' => uri.push_str("%22"),
+            _ => uri.push(c),
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drawn_from_dna() {
+        let dna = 0x1234_5678_9abc_def0;
+        assert_eq!(svg(vtypes::PLANT, dna, Some(Stage::Mature)), svg(vtypes::PLANT, dna, Some(Stage::Mature)), "same DNA should draw the same");
+        assert_ne!(svg(vtypes::PLANT, dna, Some(Stage::Mature)), svg(vtypes::PLANT, !dna, Some(Stage::Mature)), "different DNA should draw differently");
+        let flowering = svg(vtypes::PLANT, dna, Some(Stage::Flowering));
+        assert!(flowering.contains("<circle"), "flowering plants should have flowers");
+        assert!(!svg(vtypes::PLANT, dna, Some(Stage::Mature)).contains("<circle"), "mature plants shouldn't have flowers yet");
+        assert!(svg(vtypes::PLANT, dna, Some(Stage::Wilting)).contains(DRY_LEAF), "wilting leaves should be dry");
+        assert!(svg(vtypes::HARVEST, dna, None).starts_with("<svg"));
+    }
+
+    #[test]
+    fn purple_flowers() {
+        let purple = 4 << 24;
+        assert!(svg(vtypes::PLANT, purple, Some(Stage::Flowering)).contains("#8a4fb0"));
+    }
+
+    #[test]
+    fn uri_escaping() {
+        assert_eq!("data:image/svg+xml,%3Cg fill='%23fff'%3E%3C/g%3E", data_uri("<g fill='#fff'></g>"));
+    }
+}
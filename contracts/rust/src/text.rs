@@ -0,0 +1,142 @@
+//! Words for harvests.  Oracle, Compliment, Insult and Advice plants' harvests each
+//! carry a message: a fortune, a compliment, an insult or some advice.  It's made by
+//! filling in one of a grammar's templates with words from its word lists, all picked
+//! by an RNG seeded with the harvest's DNA, so anyone can check that a harvest's
+//! message is the one its DNA makes.
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use rand_seeder::Seeder;
+
+use crate::constants::{ptypes, HarvestType};
+
+// a template's {name}s are filled in with a word from the word list of that name
+pub struct Grammar {
+    templates: &'static [&'static str],
+    words: &'static [(&'static str, &'static [&'static str])],
+}
+
+const FORTUNE: Grammar = Grammar {
+    templates: &[
+        "{when}, {someone} will bring you {something}.",
+        "Your {virtue} will lead you to {something}.",
+        "Beware of {something} {when_lower}.",
+        "{someone} is thinking of you.",
+        "{when}, you will find {something} where you least expect it.",
+    ],
+    words: &[
+        ("when", &["Soon", "Before the next full moon", "Next spring", "Within a fortnight", "After the first frost"]),
+        ("when_lower", &["this week", "before the harvest", "after dark", "on Tuesday", "when the rain comes"]),
+        ("someone", &["a stranger", "an old friend", "a neighbor", "someone dressed in green", "a tall gardener", "a talking crow"]),
+        ("something", &["good news", "an unexpected gift", "a new beginning", "great fortune", "a difficult choice", "a long journey", "a lost key"]),
+        ("virtue", &["patience", "kindness", "curiosity", "honesty", "stubbornness", "green thumb"]),
+    ],
+};
+
+const COMPLIMENT: Grammar = Grammar {
+    templates: &[
+        "Your {feature} is as {good} as {nice}.",
+        "You have the most {good} {feature}.",
+        "Being around you is like {nice} on a {day} day.",
+        "Even {nice} isn't as {good} as your {feature}.",
+    ],
+    words: &[
+        ("feature", &["smile", "laugh", "sense of humor", "taste in music", "handwriting", "way with plants", "cooking"]),
+        ("good", &["radiant", "delightful", "lovely", "dazzling", "charming", "refreshing"]),
+        ("nice", &["a sunrise", "fresh basil", "a summer rain", "a sunflower", "the first day of spring", "a ripe peach"]),
+        ("day", &["grey", "long", "rainy", "busy", "cold"]),
+    ],
+};
+
+const INSULT: Grammar = Grammar {
+    templates: &[
+        "Your {feature} is as {bad} as {nasty}.",
+        "You have all the charm of {nasty}.",
+        "I've met {nasty} with more {virtue} than you.",
+        "Your {feature} would make {nasty} wilt.",
+    ],
+    words: &[
+        ("feature", &["smile", "laugh", "sense of humor", "taste in music", "handwriting", "cooking", "singing"]),
+        ("bad", &["limp", "soggy", "wilted", "bland", "mouldy", "stale"]),
+        ("nasty", &["a wilted lettuce", "a soggy radish", "a boiled potato", "a mouldy turnip", "crabgrass", "an aphid"]),
+        ("virtue", &["charm", "wit", "backbone", "sparkle", "manners"]),
+    ],
+};
+
+const ADVICE: Grammar = Grammar {
+    templates: &[
+        "Never {act} before {time}.",
+        "Always {act} when {situation}.",
+        "When {situation}, {act}.",
+        "Water your {thing} and {act}.",
+    ],
+    words: &[
+        ("act", &["plant something", "call your mother", "take a long walk", "eat a vegetable", "say thank you", "ask for help", "sleep on it"]),
+        ("time", &["breakfast", "noon", "the first frost", "you've had your tea", "the rooster crows"]),
+        ("situation", &["in doubt", "the moon is full", "it rains", "nobody is watching", "your plants look thirsty"]),
+        ("thing", &["plants", "friendships", "ideas", "dreams", "radishes"]),
+    ],
+};
+
+// the grammar for a type of harvest, if it carries a message
+pub fn grammar(htype: HarvestType) -> Option<&'static Grammar> {
+    match htype {
+        ptypes::ORACLE => Some(&FORTUNE),
+        ptypes::COMPLIMENT => Some(&COMPLIMENT),
+        ptypes::INSULT => Some(&INSULT),
+        ptypes::ADVICE => Some(&ADVICE),
+        _ => None,
+    }
+}
+
+impl Grammar {
+    fn word(&self, name: &str, rng: &mut impl Rng) -> &'static str {
+        let (_, words) = self.words.iter().find(|(n, _)| *n == name).expect("template uses an unknown word list");
+        words[rng.gen_range(0, words.len())]
+    }
+
+    // the message that DNA makes
+    pub fn generate(&self, dna: u64) -> String {
+        let mut rng: ChaCha8Rng = Seeder::from(dna).make_rng();
+        let mut rest = self.templates[rng.gen_range(0, self.templates.len())];
+        let mut text = String::new();
+        while let Some(start) = rest.find('{') {
+            let end = start + rest[start..].find('}').unwrap();
+            text.push_str(&rest[..start]);
+            text.push_str(self.word(&rest[start + 1..end], &mut rng));
+            rest = &rest[end + 1..];
+        }
+        text.push_str(rest);
+        // a template can start with a word
+        let mut chars = text.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => text,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_dna_same_text() {
+        let fortune = grammar(ptypes::ORACLE).unwrap();
+        assert_eq!(fortune.generate(42), fortune.generate(42), "text should only depend on DNA");
+        let texts: Vec<String> = (0..20).map(|dna| fortune.generate(dna)).collect();
+        assert!(texts.iter().any(|t| *t != texts[0]), "different DNA should give different texts");
+        assert!(grammar(ptypes::MONEY).is_none());
+    }
+
+    #[test]
+    fn templates_filled_in() {
+        for htype in [ptypes::ORACLE, ptypes::COMPLIMENT, ptypes::INSULT, ptypes::ADVICE] {
+            for dna in 0..50 {
+                let text = grammar(htype).unwrap().generate(dna);
+                assert!(!text.contains('{') && !text.contains('}'), "template not filled in: {}", text);
+                assert!(text.starts_with(char::is_uppercase), "text isn't capitalized: {}", text);
+            }
+        }
+    }
+}
@@ -5,10 +5,19 @@
 
 //use borsh::{BorshDeserialize, BorshSerialize};
 
+use near_sdk::collections::TreeMap;
 use near_sdk::collections::UnorderedMap;
 use near_sdk::collections::UnorderedSet;
-use near_sdk::{env, AccountId};
+use near_sdk::collections::Vector;
+use near_sdk::{env, ext_contract, json_types, AccountId, Balance, Gas, Promise, PromiseOrValue, PromiseResult};
+use std::collections::HashMap;
+use std::ops::Bound;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::Serialize;
+
+use crate::constants::STORAGE_PRICE_PER_BYTE;
+use crate::metadata::TokenMetadata;
+use crate::events;
 
 /// This trait provides the baseline of functions as described at:
 /// https://github.com/nearprotocol/NEPs/blob/nep-4/specs/Standards/Tokens/NonFungibleToken.md
@@ -41,11 +50,209 @@ pub trait NEP4 {
     fn get_token_owner(&self, token_id: TokenId) -> String;
 }
 
+/// This trait provides the core functions of NEP-171, the standard that wallets and marketplaces speak:
+/// https://github.com/near/NEPs/blob/master/specs/Standards/NonFungibleToken/Core.md
+/// Token IDs are JSON strings here, so unlike NEP4 they survive the trip through Javascript.
+pub trait NEP171 {
+    // Transfer the given `token_id` to `receiver_id`.
+    // Requirements:
+    // * The caller must attach exactly 1 yoctoNEAR, so that this can only be called with a full access key.
+    // * The caller must be the owner of the token, or be approved for it (see NEP178).
+    // * If `approval_id` is given, it must match the caller's current approval.
+    fn nft_transfer(&mut self, receiver_id: AccountId, token_id: TokenJSON, approval_id: Option<u64>, memo: Option<String>);
+
+    // Transfer the given `token_id` to `receiver_id`, then call `nft_on_transfer` on the receiver.
+    // If the receiver asks for it back (or the call fails), `nft_resolve_transfer` returns the token.
+    // Requirements are the same as for `nft_transfer`.
+    fn nft_transfer_call(&mut self, receiver_id: AccountId, token_id: TokenJSON, approval_id: Option<u64>, memo: Option<String>, msg: String) -> PromiseOrValue<bool>;
+
+    // Get the token with the given `token_id`, or null if there is no such token.
+    fn nft_token(&self, token_id: TokenJSON) -> Option<NFTokenJSON>;
+}
+
+/// Callback half of NEP-171's `nft_transfer_call`.
+pub trait NEP171Resolver {
+    // Finish a transfer started by `nft_transfer_call`, returning the token to `owner_id` (with the
+    // approvals it had) if the receiver asked for that.  Returns true if the token ended up with `receiver_id`.
+    // Requirements:
+    // * Only the contract itself may call this.
+    fn nft_resolve_transfer(&mut self, owner_id: AccountId, receiver_id: AccountId, token_id: TokenJSON, approved_account_ids: Option<HashMap<AccountId, u64>>) -> bool;
+}
+
+/// This trait provides NEP-178 per-token approvals:
+/// https://github.com/near/NEPs/blob/master/specs/Standards/NonFungibleToken/ApprovalManagement.md
+/// Unlike NEP4's grant_access, an approval covers a single token, and it is cleared when the token moves.
+pub trait NEP178 {
+    // Let `account_id` transfer `token_id`.  Every approval gets a new approval ID, even a repeated one.
+    // If `msg` is given, `nft_on_approve` is called on `account_id`.
+    // Requirements:
+    // * The caller must own the token, and attach at least 1 yoctoNEAR, and enough to pay for the storage
+    //   the approval takes up (STORAGE_PRICE_PER_BYTE a byte); any more is refunded.
+    // * With a `msg`, the caller must attach enough gas for `nft_on_approve`.
+    fn nft_approve(&mut self, token_id: TokenJSON, account_id: AccountId, msg: Option<String>);
+
+    // Take away `account_id`'s approval for `token_id`.
+    // Requirements:
+    // * The caller must own the token, and attach exactly 1 yoctoNEAR.
+    fn nft_revoke(&mut self, token_id: TokenJSON, account_id: AccountId);
+
+    // Take away every approval for `token_id`.
+    // Requirements:
+    // * The caller must own the token, and attach exactly 1 yoctoNEAR.
+    fn nft_revoke_all(&mut self, token_id: TokenJSON);
+
+    // Returns true if `approved_account_id` may transfer `token_id`
+    // (and, if `approval_id` is given, if that is its current approval ID).
+    fn nft_is_approved(&self, token_id: TokenJSON, approved_account_id: AccountId, approval_id: Option<u64>) -> bool;
+}
+
+/// This trait provides NEP-181 enumeration:
+/// https://github.com/near/NEPs/blob/master/specs/Standards/NonFungibleToken/Enumeration.md
+/// Pages are read straight out of the ledger's storage vectors, so a call only pays for the tokens it returns.
+/// Mints and transfers in append to the end; a token leaving (burn or transfer out) moves the last token into its place,
+/// so a walk through the pages can skip or repeat a token.  To see every token exactly once, page with the last token ID
+/// seen instead (see `TokenBank::tokens_after`).
+pub trait NEP181 {
+    // Total number of tokens in existence.
+    fn nft_total_supply(&self) -> json_types::U128;
+
+    // Get a page of all tokens, starting at `from_index` (default 0), at most `limit` long.
+    fn nft_tokens(&self, from_index: Option<json_types::U128>, limit: Option<u64>) -> Vec<NFTokenJSON>;
+
+    // Number of tokens owned by `account_id`.
+    fn nft_supply_for_owner(&self, account_id: AccountId) -> json_types::U128;
+
+    // Get a page of the tokens owned by `account_id`.
+    fn nft_tokens_for_owner(&self, account_id: AccountId, from_index: Option<json_types::U128>, limit: Option<u64>) -> Vec<NFTokenJSON>;
+}
+
+// Method implemented by contracts that want to receive tokens through `nft_transfer_call`.
+// Returns true if the token should be returned to the sender.
+#[ext_contract(ext_receiver)]
+pub trait NFTReceiver {
+    fn nft_on_transfer(&mut self, sender_id: AccountId, previous_owner_id: AccountId, token_id: TokenJSON, msg: String) -> PromiseOrValue<bool>;
+}
+
+// Method implemented by contracts that want to hear about approvals made with a `msg`.
+#[ext_contract(ext_approval_receiver)]
+pub trait NFTApprovalReceiver {
+    fn nft_on_approve(&mut self, token_id: TokenJSON, owner_id: AccountId, approval_id: u64, msg: String);
+}
+
+// Our own callback, as seen from the outside.
+#[ext_contract(ext_self)]
+pub trait NFTResolver {
+    fn nft_resolve_transfer(&mut self, owner_id: AccountId, receiver_id: AccountId, token_id: TokenJSON, approved_account_ids: Option<HashMap<AccountId, u64>>) -> bool;
+}
+
+const NO_DEPOSIT: Balance = 0;
+// page size for NEP-181 calls that don't give a limit
+const DEFAULT_PAGE_LIMIT: u64 = 50;
+const GAS_FOR_RESOLVE_TRANSFER: Gas = 10_000_000_000_000;
+const GAS_FOR_NFT_TRANSFER_CALL: Gas = 25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER;
+const GAS_FOR_NFT_APPROVE: Gas = 10_000_000_000_000;
+
 /// The token ID type is also defined in the NEP
 pub type TokenId = u64;
+// this is the external, JSON-compatible version for method calls.  (u64s are strings.)
+pub type TokenJSON = json_types::U64;
 pub type TokenSet = UnorderedSet<TokenId>;
 pub type AccountIdHash = Vec<u8>;
 
+// Every account's nested set gets its own storage prefix, made from a hash of the account.
+// (Before these, every owner set shared one prefix, and so did every access set; see migrate_shared_sets.)
+const OWNER_TOKENS_PREFIX: &[u8] = b"owner-tokens:";
+const ACCESS_SET_PREFIX: &[u8] = b"access-set:";
+const SHARED_OWNER_TOKENS_PREFIX: &[u8] = b"owner-tokens-set";
+const SHARED_ACCESS_SET_PREFIX: &[u8] = b"new-access-set";
+
+fn owner_tokens_prefix(account_id: &AccountId) -> Vec<u8> {
+    [OWNER_TOKENS_PREFIX, &env::sha256(account_id.as_bytes())].concat()
+}
+
+fn access_set_prefix(account_hash: &AccountIdHash) -> Vec<u8> {
+    [ACCESS_SET_PREFIX, account_hash].concat()
+}
+
+// true if the set was made with the given prefix.  (an UnorderedSet stores its prefixes first.)
+fn has_prefix<T: BorshSerialize>(set: &UnorderedSet<T>, id: &[u8]) -> bool {
+    let index_prefix = [id, b"i"].concat().try_to_vec().unwrap();
+    set.try_to_vec().unwrap().starts_with(&index_prefix)
+}
+
+// Removes the storage behind sets that all shared the prefix `id`, up to `len` elements deep.
+// The sets' own lengths and contents can't be trusted, so this works on the raw keys.
+fn remove_shared_set(id: &[u8], len: u64) {
+    let index_prefix = [id, b"i"].concat();
+    let elements_prefix = [id, b"e"].concat();
+    for i in 0..len {
+        let element_key = [&elements_prefix[..], &i.to_le_bytes()].concat();
+        if let Some(raw_element) = env::storage_read(&element_key) {
+            env::storage_remove(&[&index_prefix[..], &raw_element].concat());
+            env::storage_remove(&element_key);
+        }
+    }
+}
+
+/// The NEP-171 view of a token
+#[derive(PartialEq, Clone, Debug, Serialize)]
+pub struct NFTokenJSON {
+    pub token_id: TokenJSON,
+    pub owner_id: AccountId,
+    pub metadata: Option<TokenMetadata>, // NEP-177; the bank itself doesn't know it
+    pub approved_account_ids: HashMap<AccountId, u64>, // NEP-178
+}
+
+/// Who may transfer a token on its owner's behalf (NEP-178).
+/// The approval ID counter outlives the approvals, so an old approval ID never matches a new approval.
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct TokenApprovals {
+    pub next_approval_id: u64,
+    pub accounts: HashMap<AccountId, u64>,
+}
+
+/// Reads ids[from_index .. from_index + limit], or as much of that as exists.
+/// (The storage vectors behind UnorderedMap and UnorderedSet let us skip straight to the page.)
+pub fn page_of(ids: &Vector<TokenId>, from_index: u64, limit: u64) -> Vec<TokenId> {
+    let end = std::cmp::min(from_index.saturating_add(limit), ids.len());
+    (from_index..end).filter_map(|i| ids.get(i)).collect()
+}
+
+/// (index of the first item, how many) for a page of page_size, or everything if page_size is 0
+pub fn page_bounds(page_size: u16, page: u16) -> (u64, u64) {
+    if page_size == 0 {
+        (0, u64::MAX)
+    } else {
+        (page_size as u64 * page as u64, page_size as u64)
+    }
+}
+
+// NEP-171 asks for exactly one yoctoNEAR on transfers, which a function-call access key can't attach.
+fn assert_one_yocto() {
+    if env::attached_deposit() != 1 {
+        env::panic(b"Requires attached deposit of exactly 1 yoctoNEAR")
+    }
+}
+
+fn assert_at_least_one_yocto() {
+    if env::attached_deposit() < 1 {
+        env::panic(b"Requires attached deposit of at least 1 yoctoNEAR")
+    }
+}
+
+// Charge the caller for whatever was stored since `storage_before`, out of their deposit, and refund the rest.
+fn charge_storage_since(storage_before: u64) {
+    let cost = env::storage_usage().saturating_sub(storage_before) as Balance * STORAGE_PRICE_PER_BYTE;
+    let deposit = env::attached_deposit();
+    if deposit < cost {
+        env::panic(format!("Requires attached deposit of at least {} yoctoNEAR for storage", cost).as_bytes())
+    }
+    let refund = deposit - cost;
+    if refund > 0 {
+        Promise::new(env::predecessor_account_id()).transfer(refund);
+    }
+}
+
 // Begin implementation
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct TokenBank {
@@ -53,6 +260,11 @@ pub struct TokenBank {
     pub token_to_account: UnorderedMap<TokenId, AccountId>,
     pub account_to_tokens: UnorderedMap<AccountId, TokenSet>,
     pub account_gives_access: UnorderedMap<AccountIdHash, UnorderedSet<AccountIdHash>>, // Vec<u8> is sha256 of account, makes it safer and is how fungible token also works
+    pub token_approvals: UnorderedMap<TokenId, TokenApprovals>,
+    // the same tokens in ID order, overall and by owner, for the `*_after` cursors
+    // (mints, transfers and burns keep them up to date)
+    pub tokens_by_id: TreeMap<TokenId, ()>,
+    pub tokens_by_owner: TreeMap<(AccountId, TokenId), ()>,
 }
 
 impl TokenBank {
@@ -61,6 +273,9 @@ impl TokenBank {
             token_to_account: UnorderedMap::new(b"token-belongs-to".to_vec()),
             account_to_tokens: UnorderedMap::new(b"account-owns".to_vec()),
             account_gives_access: UnorderedMap::new(b"gives-access".to_vec()),
+            token_approvals: UnorderedMap::new(b"token-approvals".to_vec()),
+            tokens_by_id: TreeMap::new(b"tokens-by-id".to_vec()),
+            tokens_by_owner: TreeMap::new(b"tokens-by-owner".to_vec()),
         }
     }
 
@@ -70,24 +285,32 @@ impl TokenBank {
     pub fn get_owner_tokens(&self, account_id: &AccountId) -> TokenSet {
         match self.account_to_tokens.get(&account_id) {
             Some(owner_tokens) => owner_tokens,
-            None => TokenSet::new(b"owner-tokens-set".to_vec())
+            None => TokenSet::new(owner_tokens_prefix(account_id))
         }
     }
 
-    // From the total set of tokens, get a page's worth
-    // (NOTE: if we need this to be really pageable & consistent, then our Unordered Map should be
-    // replaced with an Ordered one ...)
+    // From the total set of tokens, get a page's worth (or all of them, if page_size is 0)
+    // (only the tokens on the page are read; see NEP181 for how the order holds up.)
     pub fn get_tokens_page(&self, page_size: u16, page: u16) -> Vec<TokenId> {
-        let tokens: Vec<TokenId> = self.token_to_account.keys().collect();
-        let token_count = tokens.len();
-        
-        let startpoint: usize = page_size as usize * page as usize;
-        if startpoint > token_count { return Vec::new(); }
+        let (from_index, limit) = page_bounds(page_size, page);
+        page_of(self.token_to_account.keys_as_vector(), from_index, limit)
+    }
 
-        let mut endpoint : usize =  startpoint + page_size as usize;
-        if endpoint > token_count { endpoint = token_count; }
+    // Token IDs in ID order, starting after `after` (or at the first token).  Tokens coming and going
+    // don't move the others, so paging on with the last ID of each page never skips or repeats one.
+    pub fn tokens_after(&self, after: Option<TokenId>) -> impl Iterator<Item = TokenId> + '_ {
+        // (a TreeMap range needs a lower bound)
+        let from = after.map_or(Bound::Included(0), Bound::Excluded);
+        self.tokens_by_id.range((from, Bound::Unbounded)).map(|(token_id, _)| token_id)
+    }
 
-        tokens[startpoint .. endpoint].to_vec()
+    // ... and the same for one owner's tokens
+    pub fn owner_tokens_after(&self, owner_id: &AccountId, after: Option<TokenId>) -> impl Iterator<Item = TokenId> + '_ {
+        let from = match after {
+            Some(token_id) => Bound::Excluded((owner_id.clone(), token_id)),
+            None => Bound::Included((owner_id.clone(), 0)),
+        };
+        self.tokens_by_owner.range((from, Bound::Included((owner_id.clone(), TokenId::MAX)))).map(|((_, token_id), _)| token_id)
     }
     
     /// Creates a token for owner_id, doesn't use autoincrement, fails if id is taken
@@ -103,8 +326,10 @@ impl TokenBank {
         new_owner_tokens.insert(&token_id);
         self.account_to_tokens.insert(&owner_id, &new_owner_tokens);
         self.token_to_account.insert(&token_id, &owner_id);
+        self.tokens_by_id.insert(&token_id, &());
+        self.tokens_by_owner.insert(&(owner_id.clone(), token_id), &());
 
-
+        events::nft_mint(&owner_id, &[token_id], None);
     }
 
     // burns a token
@@ -115,12 +340,144 @@ impl TokenBank {
             env::panic(b"not yours to burn")
         }
 
+        self.remove_token(token_id);
+    }
+
+    // burns a token, no questions asked.  (callers must check permissions first.)
+    pub fn remove_token(&mut self, token_id: TokenId) {
+        let owner_id = self.get_token_owner(token_id);
         let mut owner_tokens = self.get_owner_tokens(&owner_id);
         owner_tokens.remove(&token_id);
         self.account_to_tokens.insert(&owner_id, &owner_tokens);
         self.token_to_account.remove(&token_id);
+        self.token_approvals.remove(&token_id);
+        self.tokens_by_id.remove(&token_id);
+        self.tokens_by_owner.remove(&(owner_id.clone(), token_id));
+
+        let predecessor = env::predecessor_account_id();
+        let authorized_id = Some(predecessor.as_str()).filter(|p| *p != owner_id);
+        events::nft_burn(&owner_id, &[token_id], authorized_id, None);
+    }
+
+    // moves a token from one owner to another, no questions asked, and clears its approvals.
+    // (callers must check permissions first.)
+    // authorized_id and memo only go in the transfer event.
+    // returns the approvals the token had.
+    fn move_token(&mut self, old_owner_id: &AccountId, new_owner_id: &AccountId, token_id: TokenId, authorized_id: Option<&AccountId>, memo: Option<String>) -> HashMap<AccountId, u64> {
+        let mut new_owner_tokens = self.get_owner_tokens(new_owner_id);
+        let mut prev_owner_tokens = self.get_owner_tokens(old_owner_id);
+
+        // Q: if owner_tokens is now empty, would it be more NEAR-optimal to delete it from the map?
+        prev_owner_tokens.remove(&token_id);
+        new_owner_tokens.insert(&token_id);
+
+        // Q: In NEAR, is a transaction guaranteed around a smart method call?
+        // Cuz these three need to be a transaction:
+        self.token_to_account.insert(&token_id, new_owner_id);
+        self.account_to_tokens.insert(new_owner_id, &new_owner_tokens);
+        self.account_to_tokens.insert(old_owner_id, &prev_owner_tokens);
+        self.tokens_by_owner.remove(&(old_owner_id.clone(), token_id));
+        self.tokens_by_owner.insert(&(new_owner_id.clone(), token_id), &());
+
+        events::nft_transfer(old_owner_id, new_owner_id, &[token_id], authorized_id.map(|a| a.as_str()), memo.as_deref());
+
+        self.set_approvals(token_id, HashMap::new())
+    }
+
+    // Gets the approval ID that lets account_id transfer token_id, if there is one
+    pub fn get_approval_id(&self, token_id: TokenId, account_id: &AccountId) -> Option<u64> {
+        self.token_approvals.get(&token_id).and_then(|approvals| approvals.accounts.get(account_id).copied())
+    }
+
+    // replaces a token's approvals (keeping its approval ID counter); returns the old ones.
+    fn set_approvals(&mut self, token_id: TokenId, accounts: HashMap<AccountId, u64>) -> HashMap<AccountId, u64> {
+        let mut approvals = match self.token_approvals.get(&token_id) {
+            Some(approvals) => approvals,
+            None if accounts.is_empty() => return HashMap::new(),
+            None => TokenApprovals::default(),
+        };
+        let old_accounts = std::mem::replace(&mut approvals.accounts, accounts);
+        self.token_approvals.insert(&token_id, &approvals);
+        old_accounts
+    }
+
+    // panic unless the caller owns the token; returns the owner.
+    fn only_token_owner(&self, token_id: TokenId) -> AccountId {
+        let owner_id = self.get_token_owner(token_id);
+        if env::predecessor_account_id() != owner_id {
+            env::panic(b"Only the token owner can manage its approvals.")
+        }
+        owner_id
     }
 
+    /// NEP-171 transfer: checks that sender_id may move the token, moves it,
+    /// returns the previous owner and the approvals the token had.
+    pub fn internal_transfer(&mut self, sender_id: &AccountId, receiver_id: &AccountId, token_id: TokenId, approval_id: Option<u64>, memo: Option<String>) -> (AccountId, HashMap<AccountId, u64>) {
+        let owner_id = self.get_token_owner(token_id);
+        if *sender_id != owner_id {
+            match self.get_approval_id(token_id, sender_id) {
+                None => env::panic(b"Attempt to transfer a token with no access."),
+                Some(current_id) => {
+                    if approval_id.is_some() && approval_id != Some(current_id) {
+                        env::panic(b"The approval ID does not match the current approval.")
+                    }
+                }
+            }
+        }
+        if *receiver_id == owner_id {
+            env::panic(b"The token owner and the receiver should be different.")
+        }
+
+        let authorized_id = Some(sender_id).filter(|s| **s != owner_id);
+        let approved_account_ids = self.move_token(&owner_id, receiver_id, token_id, authorized_id, memo);
+
+        (owner_id, approved_account_ids)
+    }
+
+    // One-time fix for state from before each account's sets had their own prefix.
+    // Rebuilds every owner's token set from token_to_account.  NEP4 escrow access is dropped
+    // wherever it was still in a shared set, since there's no telling whose its entries were.
+    pub fn migrate_shared_sets(&mut self) {
+        // the shared storage runs as deep as the longest set, or the number of tokens
+        let mut shared_len = self.token_to_account.len();
+        for mut owner_tokens in self.account_to_tokens.values() {
+            if has_prefix(&owner_tokens, SHARED_OWNER_TOKENS_PREFIX) {
+                shared_len = std::cmp::max(shared_len, owner_tokens.len());
+            } else {
+                owner_tokens.clear(); // already has its own prefix; rebuilt below anyway
+            }
+        }
+        remove_shared_set(SHARED_OWNER_TOKENS_PREFIX, shared_len);
+
+        // rebuild, with the ID-ordered indexes; anything overwritten in the shared storage is cleared here too.
+        self.account_to_tokens.clear();
+        self.tokens_by_id.clear();
+        self.tokens_by_owner.clear();
+        let owned: Vec<(TokenId, AccountId)> = self.token_to_account.iter().collect();
+        for (token_id, owner_id) in owned {
+            let shared_index_key = [SHARED_OWNER_TOKENS_PREFIX, b"i", &token_id.try_to_vec().unwrap()].concat();
+            env::storage_remove(&shared_index_key);
+
+            let mut owner_tokens = self.get_owner_tokens(&owner_id);
+            owner_tokens.insert(&token_id);
+            self.account_to_tokens.insert(&owner_id, &owner_tokens);
+            self.tokens_by_id.insert(&token_id, &());
+            self.tokens_by_owner.insert(&(owner_id, token_id), &());
+        }
+
+        let mut shared_len = 0;
+        let mut shared_owners = vec![];
+        for (owner_hash, access_set) in self.account_gives_access.iter() {
+            if has_prefix(&access_set, SHARED_ACCESS_SET_PREFIX) {
+                shared_len = std::cmp::max(shared_len, access_set.len());
+                shared_owners.push(owner_hash);
+            }
+        }
+        remove_shared_set(SHARED_ACCESS_SET_PREFIX, shared_len);
+        for owner_hash in shared_owners {
+            self.account_gives_access.remove(&owner_hash);
+        }
+    }
 }
 
 impl Default for TokenBank {
@@ -140,7 +497,7 @@ impl NEP4 for TokenBank {
                 existing_set
             },
             None => {
-                UnorderedSet::new(b"new-access-set".to_vec())
+                UnorderedSet::new(access_set_prefix(&predecessor_hash))
             }
         };
         access_set.insert(&escrow_hash);
@@ -171,19 +528,7 @@ impl NEP4 for TokenBank {
             env::panic(b"Attempt to call transfer on tokens belonging to another account.")
         }
 
-        let mut new_owner_tokens = self.get_owner_tokens(&new_owner_id);
-        let mut prev_owner_tokens = self.get_owner_tokens(&token_owner_account_id);
-
-        new_owner_tokens.insert(&token_id);
-
-        // Q: if owner_tokens is now empty, would it be more NEAR-optimal to delete it from the map?
-        prev_owner_tokens.remove(&token_id); 
-
-        // Q: In NEAR, is a transaction guaranteed around a smart method call?
-        // Cuz these three need to be a transaction:
-        self.token_to_account.insert(&token_id, &new_owner_id);
-        self.account_to_tokens.insert(&new_owner_id, &new_owner_tokens);
-        self.account_to_tokens.insert(&token_owner_account_id, &prev_owner_tokens);
+        self.move_token(&token_owner_account_id, &new_owner_id, token_id, None, None);
     }
 
     fn transfer_from(&mut self, owner_id: AccountId, new_owner_id: AccountId, token_id: TokenId) {
@@ -192,18 +537,15 @@ impl NEP4 for TokenBank {
             env::panic(b"Attempt to transfer a token from wrong owner.")
         }
 
-        if !self.check_access(&token_owner_account_id) {
+        // NEP4 escrow access covers all of the owner's tokens; NEP-178 approvals cover just one.
+        let predecessor = env::predecessor_account_id();
+        let approved = self.get_approval_id(token_id, &predecessor).is_some();
+        if !approved && !self.check_access(&token_owner_account_id) {
             env::panic(b"Attempt to transfer a token with no access.")
         }
 
-        let mut new_owner_tokens = self.get_owner_tokens(&new_owner_id);
-        let mut prev_owner_tokens = self.get_owner_tokens(&token_owner_account_id);
-
-        new_owner_tokens.insert(&token_id);
-        prev_owner_tokens.remove(&token_id); 
-        self.token_to_account.insert(&token_id, &new_owner_id);
-        self.account_to_tokens.insert(&new_owner_id, &new_owner_tokens);
-        self.account_to_tokens.insert(&token_owner_account_id, &prev_owner_tokens);
+        let authorized_id = Some(&predecessor).filter(|p| **p != token_owner_account_id);
+        self.move_token(&token_owner_account_id, &new_owner_id, token_id, authorized_id, None);
     }
 
     fn check_access(&self, account_id: &AccountId) -> bool {
@@ -231,6 +573,167 @@ impl NEP4 for TokenBank {
     
 }
 
+impl NEP171 for TokenBank {
+    fn nft_transfer(&mut self, receiver_id: AccountId, token_id: TokenJSON, approval_id: Option<u64>, memo: Option<String>) {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer(&sender_id, &receiver_id, token_id.into(), approval_id, memo);
+    }
+
+    fn nft_transfer_call(&mut self, receiver_id: AccountId, token_id: TokenJSON, approval_id: Option<u64>, memo: Option<String>, msg: String) -> PromiseOrValue<bool> {
+        assert_one_yocto();
+        if env::prepaid_gas() <= GAS_FOR_NFT_TRANSFER_CALL {
+            env::panic(b"More gas is required")
+        }
+        let sender_id = env::predecessor_account_id();
+        let (previous_owner_id, approved_account_ids) = self.internal_transfer(&sender_id, &receiver_id, token_id.into(), approval_id, memo);
+
+        ext_receiver::nft_on_transfer(
+            sender_id,
+            previous_owner_id.clone(),
+            token_id,
+            msg,
+            &receiver_id,
+            NO_DEPOSIT,
+            env::prepaid_gas() - GAS_FOR_NFT_TRANSFER_CALL,
+        )
+        .then(ext_self::nft_resolve_transfer(
+            previous_owner_id,
+            receiver_id,
+            token_id,
+            Some(approved_account_ids),
+            &env::current_account_id(),
+            NO_DEPOSIT,
+            GAS_FOR_RESOLVE_TRANSFER,
+        ))
+        .into()
+    }
+
+    fn nft_token(&self, token_id: TokenJSON) -> Option<NFTokenJSON> {
+        let token_id_u64 = TokenId::from(token_id);
+        self.token_to_account.get(&token_id_u64).map(|owner_id| NFTokenJSON {
+            token_id,
+            owner_id,
+            metadata: None,
+            approved_account_ids: self.token_approvals.get(&token_id_u64).map(|a| a.accounts).unwrap_or_default(),
+        })
+    }
+}
+
+impl NEP181 for TokenBank {
+    fn nft_total_supply(&self) -> json_types::U128 {
+        (self.token_to_account.len() as u128).into()
+    }
+
+    fn nft_tokens(&self, from_index: Option<json_types::U128>, limit: Option<u64>) -> Vec<NFTokenJSON> {
+        let from_index = u128::from(from_index.unwrap_or(0.into())) as u64;
+        page_of(self.token_to_account.keys_as_vector(), from_index, limit.unwrap_or(DEFAULT_PAGE_LIMIT))
+            .into_iter()
+            .filter_map(|t| self.nft_token(t.into()))
+            .collect()
+    }
+
+    fn nft_supply_for_owner(&self, account_id: AccountId) -> json_types::U128 {
+        (self.get_owner_tokens(&account_id).len() as u128).into()
+    }
+
+    fn nft_tokens_for_owner(&self, account_id: AccountId, from_index: Option<json_types::U128>, limit: Option<u64>) -> Vec<NFTokenJSON> {
+        let from_index = u128::from(from_index.unwrap_or(0.into())) as u64;
+        let owner_tokens = self.get_owner_tokens(&account_id);
+        page_of(owner_tokens.as_vector(), from_index, limit.unwrap_or(DEFAULT_PAGE_LIMIT))
+            .into_iter()
+            .filter_map(|t| self.nft_token(t.into()))
+            .collect()
+    }
+}
+
+impl NEP178 for TokenBank {
+    fn nft_approve(&mut self, token_id: TokenJSON, account_id: AccountId, msg: Option<String>) {
+        assert_at_least_one_yocto();
+        if msg.is_some() && env::prepaid_gas() <= GAS_FOR_NFT_APPROVE {
+            env::panic(b"More gas is required")
+        }
+        let token_id = TokenId::from(token_id);
+        let owner_id = self.only_token_owner(token_id);
+
+        // the owner pays for the storage a new approval takes up, not the contract
+        let storage_before = env::storage_usage();
+        let mut approvals = self.token_approvals.get(&token_id).unwrap_or_default();
+        let approval_id = approvals.next_approval_id;
+        approvals.next_approval_id += 1;
+        approvals.accounts.insert(account_id.clone(), approval_id);
+        self.token_approvals.insert(&token_id, &approvals);
+        charge_storage_since(storage_before);
+
+        if let Some(msg) = msg {
+            // the promise is sent when it's dropped; the approval stands whatever the receiver says.
+            ext_approval_receiver::nft_on_approve(
+                token_id.into(),
+                owner_id,
+                approval_id,
+                msg,
+                &account_id,
+                NO_DEPOSIT,
+                env::prepaid_gas() - GAS_FOR_NFT_APPROVE,
+            );
+        }
+    }
+
+    fn nft_revoke(&mut self, token_id: TokenJSON, account_id: AccountId) {
+        assert_one_yocto();
+        let token_id = TokenId::from(token_id);
+        self.only_token_owner(token_id);
+        if let Some(mut approvals) = self.token_approvals.get(&token_id) {
+            if approvals.accounts.remove(&account_id).is_some() {
+                self.token_approvals.insert(&token_id, &approvals);
+            }
+        }
+    }
+
+    fn nft_revoke_all(&mut self, token_id: TokenJSON) {
+        assert_one_yocto();
+        let token_id = TokenId::from(token_id);
+        self.only_token_owner(token_id);
+        self.set_approvals(token_id, HashMap::new());
+    }
+
+    fn nft_is_approved(&self, token_id: TokenJSON, approved_account_id: AccountId, approval_id: Option<u64>) -> bool {
+        match self.get_approval_id(token_id.into(), &approved_account_id) {
+            Some(current_id) => approval_id.is_none() || approval_id == Some(current_id),
+            None => false,
+        }
+    }
+}
+
+impl NEP171Resolver for TokenBank {
+    fn nft_resolve_transfer(&mut self, owner_id: AccountId, receiver_id: AccountId, token_id: TokenJSON, approved_account_ids: Option<HashMap<AccountId, u64>>) -> bool {
+        if env::predecessor_account_id() != env::current_account_id() {
+            env::panic(b"nft_resolve_transfer is a private callback")
+        }
+
+        // nft_on_transfer returns true if the receiver wants to give the token back.
+        let must_return = match env::promise_result(0) {
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<bool>(&value).unwrap_or(true),
+            PromiseResult::Failed => true,
+            PromiseResult::NotReady => unreachable!(),
+        };
+        if !must_return {
+            return true;
+        }
+
+        // If the receiver already passed the token along (or burned it), it's theirs to keep.
+        let token_id = TokenId::from(token_id);
+        match self.token_to_account.get(&token_id) {
+            Some(current_owner_id) if current_owner_id == receiver_id => {
+                self.move_token(&receiver_id, &owner_id, token_id, None, None);
+                self.set_approvals(token_id, approved_account_ids.unwrap_or_default());
+                false
+            },
+            _ => true
+        }
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -239,6 +742,22 @@ mod tests {
     use near_sdk::{testing_env, VMContext};
     //use crate::token_bank::NEP4;
 
+    // like testing_env!, but also hands the contract the result of the promise it's a callback for.
+    fn set_context_with_promise_result(context: VMContext, result: PromiseResult) {
+        let storage = match env::take_blockchain_interface() {
+            Some(mut bi) => bi.as_mut_mocked_blockchain().unwrap().take_storage(),
+            None => Default::default(),
+        };
+        env::set_blockchain_interface(Box::new(MockedBlockchain::new(
+            context,
+            Default::default(),
+            Default::default(),
+            vec![result],
+            storage,
+            Default::default(),
+        )));
+    }
+
     fn joe() -> AccountId {
         "joe.testnet".to_string()
     }
@@ -249,6 +768,9 @@ mod tests {
         "mike.testnet".to_string()
     }
 
+    // plenty to pay for an approval's storage
+    const APPROVAL_DEPOSIT: Balance = 10_000_000_000_000_000_000_000;
+
     // part of writing unit tests is setting up a mock context
     // this is a useful list to peek at when wondering what's available in env::*
     fn get_context(predecessor_account_id: String, storage_usage: u64) -> VMContext {
@@ -551,5 +1073,397 @@ mod tests {
             let tokens = tb.get_tokens_page(100,0);
             assert_eq!(tokens.len(), 23, "bad token total page size");
         }
-}
+        #[test]
+        fn nft_transfer_own_token() {
+            let mut context = get_context(robert(), 0);
+            context.attached_deposit = 1;
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+            let token_id = 19u64;
+            tb.mint_token(robert(), token_id);
+
+            tb.nft_transfer(joe(), token_id.into(), None, Some("enjoy".to_string()));
+
+            let token = tb.nft_token(token_id.into()).unwrap();
+            assert_eq!(joe(), token.owner_id, "Token was not transferred after nft_transfer.");
+            assert_eq!(None, tb.nft_token(20u64.into()), "Found a token that was never minted.");
+        }
+
+        #[test]
+        #[should_panic(
+            expected = r#"Requires attached deposit of exactly 1 yoctoNEAR"#
+        )]
+        fn nft_transfer_without_yocto_fails() {
+            testing_env!(get_context(robert(), 0));
+            let mut tb = TokenBank::new();
+            let token_id = 19u64;
+            tb.mint_token(robert(), token_id);
+            tb.nft_transfer(joe(), token_id.into(), None, None);
+        }
+
+        #[test]
+        fn nft_transfer_with_approval() {
+            // Mike approves Robert for one token
+            let mut context = get_context(mike(), 0);
+            context.attached_deposit = APPROVAL_DEPOSIT;
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+            let token_id = 19u64;
+            tb.mint_token(mike(), token_id);
+            tb.nft_approve(token_id.into(), robert(), None);
+            assert!(tb.nft_is_approved(token_id.into(), robert(), Some(0)), "Robert should hold approval 0.");
+
+            // Robert transfers the token to Joe
+            context = get_context(robert(), env::storage_usage());
+            context.attached_deposit = 1;
+            testing_env!(context);
+            tb.nft_transfer(joe(), token_id.into(), Some(0), None);
+
+            assert_eq!(joe(), tb.get_token_owner(token_id), "Token was not transferred after nft_transfer with approval.");
+            assert!(!tb.nft_is_approved(token_id.into(), robert(), None), "Approvals should be cleared by a transfer.");
+        }
+
+        #[test]
+        #[should_panic(
+            expected = r#"Attempt to transfer a token with no access."#
+        )]
+        fn nft_transfer_with_escrow_access_fails() {
+            // Mike gives Robert NEP4 escrow access to all his tokens ...
+            let mut context = get_context(mike(), 0);
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+            let token_id = 19u64;
+            tb.mint_token(mike(), token_id);
+            tb.grant_access(robert());
+
+            // ... which doesn't count as a NEP-178 approval.
+            context = get_context(robert(), env::storage_usage());
+            context.attached_deposit = 1;
+            testing_env!(context);
+            tb.nft_transfer(joe(), token_id.into(), None, None);
+        }
+
+        #[test]
+        #[should_panic(
+            expected = r#"The approval ID does not match the current approval."#
+        )]
+        fn nft_transfer_with_stale_approval_fails() {
+            let mut context = get_context(mike(), 0);
+            context.attached_deposit = APPROVAL_DEPOSIT;
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+            let token_id = 19u64;
+            tb.mint_token(mike(), token_id);
+            // approving twice makes a new approval ID
+            tb.nft_approve(token_id.into(), robert(), None);
+            context = get_context(mike(), env::storage_usage());
+            context.attached_deposit = APPROVAL_DEPOSIT;
+            testing_env!(context);
+            tb.nft_approve(token_id.into(), robert(), None);
+
+            context = get_context(robert(), env::storage_usage());
+            context.attached_deposit = 1;
+            testing_env!(context);
+            tb.nft_transfer(joe(), token_id.into(), Some(0), None);
+        }
+
+        #[test]
+        fn nft_approve_revoke() {
+            testing_env!(get_context(mike(), 0));
+            let mut tb = TokenBank::new();
+            let token_id = 19u64;
+            tb.mint_token(mike(), token_id);
+            tb.mint_token(mike(), token_id + 1);
+
+            let mut c = get_context(mike(), env::storage_usage());
+            c.attached_deposit = APPROVAL_DEPOSIT;
+            testing_env!(c);
+            tb.nft_approve(token_id.into(), robert(), None);
+            c = get_context(mike(), env::storage_usage());
+            c.attached_deposit = APPROVAL_DEPOSIT;
+            testing_env!(c);
+            tb.nft_approve(token_id.into(), joe(), Some("list it".to_string()));
+            assert!(tb.nft_is_approved(token_id.into(), joe(), Some(1)), "Joe should hold approval 1.");
+            assert!(!tb.nft_is_approved((token_id + 1).into(), joe(), None), "Approvals only cover one token.");
+            assert_eq!(2, tb.nft_token(token_id.into()).unwrap().approved_account_ids.len(), "nft_token should list approvals.");
+
+            c = get_context(mike(), env::storage_usage());
+            c.attached_deposit = 1;
+            testing_env!(c);
+            tb.nft_revoke(token_id.into(), joe());
+            assert!(!tb.nft_is_approved(token_id.into(), joe(), None), "Joe's approval was not revoked.");
+            assert!(tb.nft_is_approved(token_id.into(), robert(), None), "Robert's approval should survive.");
+
+            tb.nft_revoke_all(token_id.into());
+            assert!(!tb.nft_is_approved(token_id.into(), robert(), None), "Robert's approval was not revoked.");
+
+            // approval IDs keep counting up
+            c = get_context(mike(), env::storage_usage());
+            c.attached_deposit = APPROVAL_DEPOSIT;
+            testing_env!(c);
+            tb.nft_approve(token_id.into(), robert(), None);
+            assert!(tb.nft_is_approved(token_id.into(), robert(), Some(2)), "Approval IDs should not be reused.");
+        }
+
+        #[test]
+        #[should_panic(
+            expected = r#"Only the token owner can manage its approvals."#
+        )]
+        fn nft_approve_not_yours() {
+            let mut context = get_context(robert(), 0);
+            context.attached_deposit = 1;
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+            tb.mint_token(mike(), 19u64);
+            tb.nft_approve(19u64.into(), robert(), None);
+        }
+
+        #[test]
+        #[should_panic(
+            expected = r#"More gas is required"#
+        )]
+        fn nft_approve_msg_low_gas() {
+            let mut context = get_context(mike(), 0);
+            context.attached_deposit = 1;
+            context.prepaid_gas = GAS_FOR_NFT_APPROVE / 2;
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+            tb.mint_token(mike(), 19u64);
+            tb.nft_approve(19u64.into(), robert(), Some("list it".to_string()));
+        }
+
+        #[test]
+        #[should_panic(
+            expected = r#"for storage"#
+        )]
+        fn nft_approve_storage_unpaid() {
+            let mut context = get_context(mike(), 0);
+            context.attached_deposit = 1;
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+            tb.mint_token(mike(), 19u64);
+            tb.nft_approve(19u64.into(), robert(), None);
+        }
+
+        #[test]
+        fn nft_approve_storage_refund() {
+            testing_env!(get_context(mike(), 0));
+            let mut tb = TokenBank::new();
+            tb.mint_token(mike(), 19u64);
+            let mut context = get_context(mike(), env::storage_usage());
+            context.attached_deposit = APPROVAL_DEPOSIT;
+            testing_env!(context);
+            let storage_before = env::storage_usage();
+            tb.nft_approve(19u64.into(), robert(), None);
+            let cost = (env::storage_usage() - storage_before) as Balance * STORAGE_PRICE_PER_BYTE;
+            assert!(cost > 0, "the approval should take up storage");
+            let receipts = near_sdk::serde_json::to_string(&env::created_receipts()).unwrap();
+            assert!(receipts.contains(&format!(r#"{{"Transfer":{{"deposit":{}}}}}"#, APPROVAL_DEPOSIT - cost)), "the rest should be refunded: {}", receipts);
+        }
+
+        #[test]
+        fn transfer_from_with_approval() {
+            let mut context = get_context(mike(), 0);
+            context.attached_deposit = APPROVAL_DEPOSIT;
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+            let token_id = 19u64;
+            tb.mint_token(mike(), token_id);
+            tb.nft_approve(token_id.into(), robert(), None);
+
+            // Robert uses the NEP4 method
+            context = get_context(robert(), env::storage_usage());
+            testing_env!(context);
+            tb.transfer_from(mike(), joe(), token_id);
+            assert_eq!(joe(), tb.get_token_owner(token_id), "Token was not transferred after transfer_from with approval.");
+        }
+
+        #[test]
+        #[should_panic(
+            expected = r#"Attempt to transfer a token with no access."#
+        )]
+        fn nft_transfer_with_no_access_should_fail() {
+            let mut context = get_context(robert(), 0);
+            context.attached_deposit = 1;
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+            let token_id = 19u64;
+            tb.mint_token(mike(), token_id);
+            tb.nft_transfer(robert(), token_id.into(), None, None);
+        }
 
+        #[test]
+        fn nft_transfer_call_kept() {
+            let mut context = get_context(robert(), 0);
+            context.attached_deposit = 1;
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+            let token_id = 19u64;
+            tb.mint_token(robert(), token_id);
+
+            tb.nft_transfer_call(joe(), token_id.into(), None, None, "keep it".to_string());
+            assert_eq!(joe(), tb.get_token_owner(token_id), "Token was not transferred by nft_transfer_call.");
+
+            // the contract calls itself back; Joe kept the token.
+            let contract_id = get_context(robert(), 0).current_account_id;
+            set_context_with_promise_result(get_context(contract_id, env::storage_usage()), PromiseResult::Successful(b"false".to_vec()));
+            assert!(tb.nft_resolve_transfer(robert(), joe(), token_id.into(), None), "Resolve should report the token as transferred.");
+            assert_eq!(joe(), tb.get_token_owner(token_id), "Kept token was returned.");
+        }
+
+        #[test]
+        fn nft_transfer_call_returned() {
+            let mut context = get_context(robert(), 0);
+            context.attached_deposit = 1;
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+            let token_id = 19u64;
+            tb.mint_token(robert(), token_id);
+
+            tb.nft_transfer_call(joe(), token_id.into(), None, None, "send it back".to_string());
+
+            // the contract calls itself back; Joe asked for the token to be returned.
+            let contract_id = get_context(robert(), 0).current_account_id;
+            set_context_with_promise_result(get_context(contract_id, env::storage_usage()), PromiseResult::Successful(b"true".to_vec()));
+            assert!(!tb.nft_resolve_transfer(robert(), joe(), token_id.into(), None), "Resolve should report the token as returned.");
+            assert_eq!(robert(), tb.get_token_owner(token_id), "Rejected token was not returned.");
+            assert_eq!(1, tb.get_owner_tokens(&robert()).len(), "Returned token missing from owner's set.");
+            assert_eq!(0, tb.get_owner_tokens(&joe()).len(), "Returned token still in receiver's set.");
+        }
+
+        #[test]
+        fn owner_sets_are_separate() {
+            let context = get_context(robert(), 0);
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+            tb.mint_token(robert(), 1);
+            tb.mint_token(joe(), 2);
+            tb.mint_token(joe(), 3);
+
+            // a token going back and forth between owners
+            tb.transfer(joe(), 1);
+            let context = get_context(joe(), env::storage_usage());
+            testing_env!(context);
+            tb.transfer(robert(), 1);
+
+            let robert_tokens: Vec<TokenId> = tb.get_owner_tokens(&robert()).to_vec();
+            let mut joe_tokens: Vec<TokenId> = tb.get_owner_tokens(&joe()).to_vec();
+            joe_tokens.sort();
+            assert_eq!(vec![1], robert_tokens, "Robert's set is wrong.");
+            assert_eq!(vec![2, 3], joe_tokens, "Joe's set is wrong.");
+
+            // access sets too
+            tb.grant_access(mike());
+            let context = get_context(mike(), env::storage_usage());
+            testing_env!(context);
+            assert!(tb.check_access(&joe()), "Joe gave Mike access.");
+            assert!(!tb.check_access(&robert()), "Robert gave Mike nothing.");
+        }
+
+        #[test]
+        fn migrate_shared_sets() {
+            let context = get_context(robert(), 0);
+            testing_env!(context);
+            let mut tb = TokenBank::new();
+
+            // the old way: everybody's sets share one prefix, and overwrite each other.
+            let mut robert_tokens = TokenSet::new(SHARED_OWNER_TOKENS_PREFIX.to_vec());
+            robert_tokens.insert(&1);
+            let mut joe_tokens = TokenSet::new(SHARED_OWNER_TOKENS_PREFIX.to_vec());
+            joe_tokens.insert(&2);
+            joe_tokens.insert(&3);
+            tb.token_to_account.insert(&1, &robert());
+            tb.token_to_account.insert(&2, &joe());
+            tb.token_to_account.insert(&3, &joe());
+            tb.account_to_tokens.insert(&robert(), &robert_tokens);
+            tb.account_to_tokens.insert(&joe(), &joe_tokens);
+            let mut access = UnorderedSet::new(SHARED_ACCESS_SET_PREFIX.to_vec());
+            access.insert(&env::sha256(mike().as_bytes()));
+            tb.account_gives_access.insert(&env::sha256(joe().as_bytes()), &access);
+            assert_eq!(vec![2], tb.get_owner_tokens(&robert()).to_vec(), "Expected the old sets to be corrupt.");
+
+            tb.migrate_shared_sets();
+
+            let mut joe_tokens: Vec<TokenId> = tb.get_owner_tokens(&joe()).to_vec();
+            joe_tokens.sort();
+            assert_eq!(vec![1], tb.get_owner_tokens(&robert()).to_vec(), "Robert's set was not rebuilt.");
+            assert_eq!(vec![2, 3], joe_tokens, "Joe's set was not rebuilt.");
+            assert_eq!(0, tb.account_gives_access.len(), "Shared access sets should be dropped.");
+            for id in [SHARED_OWNER_TOKENS_PREFIX, SHARED_ACCESS_SET_PREFIX].iter() {
+                assert!(!env::storage_has_key(&[*id, b"e", &0u64.to_le_bytes()].concat()), "Shared storage left behind.");
+            }
+            assert!(!env::storage_has_key(&[SHARED_OWNER_TOKENS_PREFIX, b"i", &1u64.to_le_bytes()].concat()), "Shared index left behind.");
+
+            // the rebuilt sets behave
+            tb.transfer(joe(), 1);
+            assert_eq!(0, tb.get_owner_tokens(&robert()).len(), "Transfer out of a rebuilt set failed.");
+            assert_eq!(3, tb.get_owner_tokens(&joe()).len(), "Transfer into a rebuilt set failed.");
+        }
+
+        #[test]
+        #[should_panic(
+            expected = r#"nft_resolve_transfer is a private callback"#
+        )]
+        fn nft_resolve_transfer_is_private() {
+            testing_env!(get_context(robert(), 0));
+            let mut tb = TokenBank::new();
+            tb.mint_token(joe(), 19u64);
+            tb.nft_resolve_transfer(robert(), joe(), 19u64.into(), None);
+        }
+
+        #[test]
+        fn nft_tokens_pages() {
+            testing_env!(get_context(robert(), 0));
+            let mut tb = TokenBank::new();
+            let token_id = 19u64;
+
+            // mint 23 tokens
+            for n in 0..23 {
+                tb.mint_token(robert(), token_id + n);
+            }
+            assert_eq!(23, u128::from(tb.nft_total_supply()), "bad total supply");
+            assert_eq!(23, u128::from(tb.nft_supply_for_owner(robert())), "bad owner supply");
+            assert_eq!(0, u128::from(tb.nft_supply_for_owner(joe())), "joe owns nothing");
+
+            // pages pick up where the last one left off
+            let first = tb.nft_tokens(None, Some(10));
+            let second = tb.nft_tokens(Some(10.into()), Some(10));
+            let last = tb.nft_tokens(Some(20.into()), Some(10));
+            assert_eq!(10, first.len(), "bad first page size");
+            assert_eq!(10, second.len(), "bad second page size");
+            assert_eq!(3, last.len(), "bad end page size");
+            assert_eq!(u64::from(first[9].token_id) + 1, u64::from(second[0].token_id), "pages should be contiguous");
+            assert_eq!(0, tb.nft_tokens(Some(100.into()), Some(10)).len(), "bad blank page size");
+
+            // no limit gets a default-sized page
+            assert_eq!(23, tb.nft_tokens(None, None).len(), "bad default page");
+
+            let owned = tb.nft_tokens_for_owner(robert(), Some(20.into()), Some(7));
+            assert_eq!(3, owned.len(), "bad owner end page size");
+            assert_eq!(robert(), owned[0].owner_id, "bad owner");
+            assert_eq!(0, tb.nft_tokens_for_owner(joe(), None, None).len(), "joe owns nothing");
+        }
+
+        #[test]
+        fn tokens_after_cursor() {
+            testing_env!(get_context(robert(), 0));
+            let mut tb = TokenBank::new();
+            for token_id in [50u64, 10, 40, 20, 30].iter() {
+                tb.mint_token(robert(), *token_id);
+            }
+            let first: Vec<TokenId> = tb.tokens_after(None).take(2).collect();
+            assert_eq!(vec![10, 20], first, "tokens should come in ID order");
+
+            // tokens coming and going don't throw off the next page
+            tb.burn_token(10);
+            tb.transfer(joe(), 40);
+            tb.mint_token(robert(), 5);
+            let rest: Vec<TokenId> = tb.tokens_after(Some(20)).take(10).collect();
+            assert_eq!(vec![30, 40, 50], rest, "the next page should start where the last left off");
+
+            let roberts: Vec<TokenId> = tb.owner_tokens_after(&robert(), Some(5)).collect();
+            assert_eq!(vec![20, 30, 50], roberts, "bad owner page");
+            assert_eq!(vec![40], tb.owner_tokens_after(&joe(), None).collect::<Vec<TokenId>>(), "joe's token should follow him");
+            assert_eq!(0, tb.owner_tokens_after(&joe(), Some(40)).count(), "nothing after joe's last token");
+        }
+}